@@ -388,6 +388,15 @@ export default function AuditorApp() {
       return;
     }
     
+    // 🏘️ gateway multi-tenant: X-Tenant-Id + API key do tenant
+    const gateway = await dbs.dbConfig.get('gateway');
+    const tenantId: string = gateway?.tenant_id || 'TERRADOURADA_URN_01';
+    const apiKey: string | undefined = gateway?.api_key;
+    if (!apiKey) {
+      addLog('⚠️ API key do tenant não configurada (terra_dourada_config_gateway)', 'warning');
+      return;
+    }
+    
     setLoading(true);
     try {
       const pendentesValidas = provas.filter(doc => 
@@ -399,14 +408,11 @@ export default function AuditorApp() {
       for (let doc of pendentesValidas) {
         try {
           const payload = {
-            nome_produtor: doc.eleitor,
-            produto: doc.candidato_nome,
-            screenshot_hash: doc.screenshot_hash,
-            tipo: "prova_presidencial",
-            timestamp_original: doc.timestamp,
-            hmac_integridade: doc.hmac_integridade,
-            validacao_zk: true,
-            dados_sensores: dadosSensores
+            schema: "vote.v1",
+            autor: doc.eleitor,
+            candidato: doc.candidato_nome,
+            eleicao_id: doc.eleicao_id,
+            screenshot_hash: doc.screenshot_hash
           };
 
           const controller = new AbortController();
@@ -414,7 +420,11 @@ export default function AuditorApp() {
 
           const response = await fetch("http://127.0.0.1:8080/mel", {
             method: "POST",
-            headers: { "Content-Type": "application/json" },
+            headers: {
+              "Content-Type": "application/json",
+              "X-Tenant-Id": tenantId,
+              "Authorization": `Bearer ${apiKey}`
+            },
             body: JSON.stringify(payload),
             signal: controller.signal
           });
@@ -0,0 +1,351 @@
+// =========================================================
+// 📦 BATCHER — agrega provas e fecha batches por tamanho/tempo
+// =========================================================
+//
+// Disco:
+//   pending.jsonl        → provas aceitas ainda sem batch (journal)
+//   batches/NNNNNNNN.json → artefato de cada batch fechado
+//   index/               → ver inclusion.rs
+//
+// O artefato é gravado (tmp + rename) ANTES de limpar o journal; se o
+// processo cair entre os dois passos, o open() descarta do journal as
+// seqs que já estão num batch fechado. Uma última linha sem '\n' é um
+// append rasgado e é descartada; qualquer outra linha inválida impede a
+// abertura.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use terra_dourada_core::merkle::{MerkleTree, PathStep};
+
+use crate::inclusion::LeafIndex;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PendingLeaf {
+    pub seq: u64,
+    pub proof_hash: String,
+    pub proof_bytes_hex: String,
+    pub received_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchLeaf {
+    pub seq: u64,
+    pub proof_hash: String,
+    pub proof_bytes_hex: String,
+    pub path: Vec<PathStep>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchArtifact {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub batch_id: u64,
+    pub root_hash: String,
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub leaf_count: usize,
+    pub operator: String,
+    pub timestamp: i64,
+    pub leaves: Vec<BatchLeaf>,
+}
+
+pub struct Batcher {
+    dir: PathBuf,
+    pending: Vec<PendingLeaf>,
+    opened_at: Option<Instant>,
+    next_seq: u64,
+    next_batch_id: u64,
+    pub max_size: usize,
+    pub max_wait: Duration,
+    pub index: LeafIndex,
+}
+
+impl Batcher {
+    pub fn open(dir: &str, max_size: usize, max_wait: Duration) -> io::Result<Self> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(dir.join("batches"))?;
+
+        // último batch fechado → próximos ids
+        let mut last: Option<BatchArtifact> = None;
+        let mut names: Vec<String> = fs::read_dir(dir.join("batches"))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|n| n.ends_with(".json"))
+            .collect();
+        names.sort();
+
+        if let Some(name) = names.last() {
+            let txt = fs::read_to_string(dir.join("batches").join(name))?;
+            last = Some(
+                serde_json::from_str(&txt)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            );
+        }
+
+        let (next_batch_id, mut next_seq) = match &last {
+            Some(b) => (b.batch_id + 1, b.last_seq + 1),
+            None => (0, 0),
+        };
+
+        // journal de pendentes
+        let mut pending = Vec::new();
+        let journal = dir.join("pending.jsonl");
+        if journal.exists() {
+            let txt = fs::read_to_string(&journal)?;
+            let completo = txt.ends_with('\n');
+            let linhas: Vec<&str> = txt.lines().collect();
+            for (i, line) in linhas.iter().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let leaf = match serde_json::from_str::<PendingLeaf>(line.trim()) {
+                    Ok(leaf) => leaf,
+                    // só a última linha, sem '\n', pode estar rasgada
+                    Err(_) if i + 1 == linhas.len() && !completo => break,
+                    Err(e) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("{:?} corrompido na linha {}: {}", journal, i + 1, e),
+                        ))
+                    }
+                };
+                if leaf.seq >= next_seq {
+                    next_seq = leaf.seq + 1;
+                    pending.push(leaf);
+                }
+            }
+        }
+
+        let opened_at = if pending.is_empty() { None } else { Some(Instant::now()) };
+
+        let index = LeafIndex::open(&dir.to_string_lossy())?;
+
+        let batcher = Self {
+            dir,
+            pending,
+            opened_at,
+            next_seq,
+            next_batch_id,
+            max_size: max_size.max(1),
+            max_wait,
+            index,
+        };
+        batcher.rewrite_journal()?;
+        Ok(batcher)
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Id que o batch aberto vai receber ao fechar.
+    pub fn open_batch_id(&self) -> u64 {
+        self.next_batch_id
+    }
+
+    /// Artefato de um batch já fechado.
+    pub fn artifact(&self, batch_id: u64) -> io::Result<Option<BatchArtifact>> {
+        let path = self.dir.join("batches").join(format!("{:08}.json", batch_id));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let txt = fs::read_to_string(path)?;
+        serde_json::from_str(&txt)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Enfileira a prova no batch aberto (journal + fsync). Retorna a seq.
+    pub fn push(&mut self, proof_hash: &[u8; 32], proof_bytes: &[u8]) -> io::Result<u64> {
+        let leaf = PendingLeaf {
+            seq: self.next_seq,
+            proof_hash: hex::encode(proof_hash),
+            proof_bytes_hex: hex::encode(proof_bytes),
+            received_at: chrono::Utc::now().timestamp(),
+        };
+
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join("pending.jsonl"))?;
+        writeln!(f, "{}", serde_json::to_string(&leaf)?)?;
+        f.sync_data()?;
+
+        self.next_seq += 1;
+        if self.pending.is_empty() {
+            self.opened_at = Some(Instant::now());
+        }
+        self.pending.push(leaf);
+        Ok(self.next_seq - 1)
+    }
+
+    /// Desfaz o último push (falha logo depois de enfileirar).
+    pub fn discard(&mut self, seq: u64) -> io::Result<()> {
+        if self.pending.last().map(|l| l.seq) != Some(seq) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("seq {} não é a última pendente", seq)));
+        }
+        self.pending.pop();
+        self.next_seq -= 1;
+        if self.pending.is_empty() {
+            self.opened_at = None;
+        }
+        self.rewrite_journal()
+    }
+
+    /// Provas do batch aberto, na ordem do journal.
+    pub fn pending(&self) -> &[PendingLeaf] {
+        &self.pending
+    }
+
+    pub fn should_close(&self) -> bool {
+        if self.pending.len() >= self.max_size {
+            return true;
+        }
+        matches!(self.opened_at, Some(t) if t.elapsed() >= self.max_wait)
+    }
+
+    /// Fecha o batch aberto: monta a árvore, grava o artefato e limpa o journal.
+    pub fn close(&mut self, operator: &str) -> io::Result<Option<BatchArtifact>> {
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+
+        let hashes: Vec<[u8; 32]> = self
+            .pending
+            .iter()
+            .map(|l| {
+                hex::decode(&l.proof_hash)
+                    .ok()
+                    .and_then(|b| b.try_into().ok())
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "proof_hash inválido"))
+            })
+            .collect::<io::Result<_>>()?;
+
+        let tree = MerkleTree::build(&hashes).unwrap();
+
+        let leaves = self
+            .pending
+            .iter()
+            .enumerate()
+            .map(|(i, l)| BatchLeaf {
+                seq: l.seq,
+                proof_hash: l.proof_hash.clone(),
+                proof_bytes_hex: l.proof_bytes_hex.clone(),
+                path: tree.path(i),
+            })
+            .collect();
+
+        let artifact = BatchArtifact {
+            kind: "terra_dourada_batch".into(),
+            batch_id: self.next_batch_id,
+            root_hash: hex::encode(tree.root()),
+            first_seq: self.pending.first().unwrap().seq,
+            last_seq: self.pending.last().unwrap().seq,
+            leaf_count: tree.leaf_count(),
+            operator: operator.to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            leaves,
+        };
+
+        self.index.record(artifact.batch_id, &hashes)?;
+        self.write_artifact(&artifact)?;
+
+        self.pending.clear();
+        self.opened_at = None;
+        self.next_batch_id += 1;
+        self.rewrite_journal()?;
+
+        Ok(Some(artifact))
+    }
+
+    fn write_artifact(&self, artifact: &BatchArtifact) -> io::Result<()> {
+        let final_path = self.dir.join("batches").join(format!("{:08}.json", artifact.batch_id));
+        let tmp_path = final_path.with_extension("json.tmp");
+
+        let mut f = File::create(&tmp_path)?;
+        f.write_all(serde_json::to_string_pretty(artifact)?.as_bytes())?;
+        f.sync_all()?;
+        fs::rename(&tmp_path, &final_path)
+    }
+
+    fn rewrite_journal(&self) -> io::Result<()> {
+        let path = self.dir.join("pending.jsonl");
+        let tmp = self.dir.join("pending.jsonl.tmp");
+
+        let mut f = File::create(&tmp)?;
+        for leaf in &self.pending {
+            writeln!(f, "{}", serde_json::to_string(leaf)?)?;
+        }
+        f.sync_all()?;
+        fs::rename(&tmp, &path)
+    }
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(nome: &str) -> String {
+        let mut p = std::env::temp_dir();
+        p.push(format!("batcher_{}_{}", std::process::id(), nome));
+        let _ = fs::remove_dir_all(&p);
+        p.to_string_lossy().into_owned()
+    }
+
+    fn abrir(d: &str) -> io::Result<Batcher> {
+        Batcher::open(d, 64, Duration::from_secs(30))
+    }
+
+    fn journal_com_duas(nome: &str) -> (String, String) {
+        let d = dir(nome);
+        let mut b = abrir(&d).unwrap();
+        b.push(&[1; 32], b"a").unwrap();
+        b.push(&[2; 32], b"b").unwrap();
+        let journal = format!("{}/pending.jsonl", d);
+        let txt = fs::read_to_string(&journal).unwrap();
+        (d, txt)
+    }
+
+    #[test]
+    fn cauda_rasgada_descartada() {
+        let (d, mut txt) = journal_com_duas("cauda");
+        txt.push_str("{\"seq\":2,\"proof_ha");
+        fs::write(format!("{}/pending.jsonl", d), txt).unwrap();
+
+        let b = abrir(&d).unwrap();
+        assert_eq!(b.pending_len(), 2);
+        let _ = fs::remove_dir_all(&d);
+    }
+
+    #[test]
+    fn corrupcao_no_meio_recusada() {
+        let (d, txt) = journal_com_duas("meio");
+        let txt = txt.replacen("\"seq\":0", "\"seq\":x", 1);
+        fs::write(format!("{}/pending.jsonl", d), txt).unwrap();
+
+        assert_eq!(abrir(&d).err().map(|e| e.kind()), Some(io::ErrorKind::InvalidData));
+        let _ = fs::remove_dir_all(&d);
+    }
+
+    #[test]
+    fn discard_desfaz_o_ultimo_push() {
+        let (d, _) = journal_com_duas("discard");
+        let mut b = abrir(&d).unwrap();
+        assert!(b.discard(0).is_err());
+        b.discard(1).unwrap();
+        assert_eq!(b.push(&[3; 32], b"c").unwrap(), 1);
+
+        let b = abrir(&d).unwrap();
+        let hashes: Vec<&str> = b.pending().iter().map(|l| l.proof_hash.as_str()).collect();
+        assert_eq!(hashes, [hex::encode([1; 32]), hex::encode([3; 32])]);
+        let _ = fs::remove_dir_all(&d);
+    }
+}
@@ -0,0 +1,139 @@
+// =========================================================
+// 🔎 INCLUSION — índice folha → batch e provas de inclusão
+// =========================================================
+//
+// index/xx.bin: registros fixos de 44 bytes
+//   proof_hash (32) | batch_id (u64 LE) | leaf_index (u32 LE)
+// bucketizados pelo primeiro byte do hash, como o anti-replay.
+//
+// O estado da ancoragem (recibo da chain, CID do batch) vem do outbox.
+//
+// Uma entrada do índice é só um ponteiro: a resposta sempre é montada a
+// partir do artefato do batch, conferindo que a folha bate com o hash.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use terra_dourada_core::merkle::PathStep;
+
+use crate::batcher::BatchArtifact;
+use crate::outbox::JobState;
+use crate::settlement::SettlementReceipt;
+
+const INDEX_RECORD_LEN: usize = 44;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InclusionProof {
+    pub proof_hash: String,
+    pub batch_id: u64,
+    pub leaf_index: u32,
+    pub seq: u64,
+    pub root_hash: String,
+    pub path: Vec<PathStep>,
+    pub anchor_state: Option<JobState>,
+    pub settlement: Option<SettlementReceipt>,
+    pub batch_cid: Option<String>,
+}
+
+pub struct LeafIndex {
+    dir: PathBuf,
+}
+
+impl LeafIndex {
+    pub fn open(dir: &str) -> io::Result<Self> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(dir.join("index"))?;
+
+        // descarta caudas rasgadas
+        for b in 0..=255u8 {
+            let path = dir.join("index").join(format!("{:02x}.bin", b));
+            if let Ok(meta) = fs::metadata(&path) {
+                let torn = meta.len() % INDEX_RECORD_LEN as u64;
+                if torn != 0 {
+                    OpenOptions::new().write(true).open(&path)?.set_len(meta.len() - torn)?;
+                }
+            }
+        }
+
+        Ok(Self { dir })
+    }
+
+    fn bucket(&self, h: &[u8; 32]) -> PathBuf {
+        self.dir.join("index").join(format!("{:02x}.bin", h[0]))
+    }
+
+    /// Indexa todas as folhas de um batch (chamado antes de gravar o artefato).
+    pub fn record(&self, batch_id: u64, hashes: &[[u8; 32]]) -> io::Result<()> {
+        for (i, h) in hashes.iter().enumerate() {
+            let mut rec = Vec::with_capacity(INDEX_RECORD_LEN);
+            rec.extend_from_slice(h);
+            rec.extend_from_slice(&batch_id.to_le_bytes());
+            rec.extend_from_slice(&(i as u32).to_le_bytes());
+
+            let mut f = OpenOptions::new().create(true).append(true).open(self.bucket(h))?;
+            f.write_all(&rec)?;
+            f.sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Todas as posições (batch_id, leaf_index) registradas para o hash.
+    pub fn lookup(&self, h: &[u8; 32]) -> io::Result<Vec<(u64, u32)>> {
+        let path = self.bucket(h);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut data = Vec::new();
+        File::open(&path)?.read_to_end(&mut data)?;
+
+        Ok(data
+            .chunks_exact(INDEX_RECORD_LEN)
+            .filter(|rec| &rec[..32] == h)
+            .map(|rec| {
+                (
+                    u64::from_le_bytes(rec[32..40].try_into().unwrap()),
+                    u32::from_le_bytes(rec[40..44].try_into().unwrap()),
+                )
+            })
+            .collect())
+    }
+
+    /// Monta a prova de inclusão a partir do artefato do batch.
+    /// Os campos de ancoragem ficam vazios: quem chama completa com o outbox.
+    pub fn inclusion(&self, h: &[u8; 32]) -> io::Result<Option<InclusionProof>> {
+        let wanted = hex::encode(h);
+
+        for (batch_id, leaf_index) in self.lookup(h)? {
+            let artifact_path = self.dir.join("batches").join(format!("{:08}.json", batch_id));
+            let Ok(txt) = fs::read_to_string(&artifact_path) else {
+                continue; // batch não chegou a ser gravado
+            };
+            let artifact: BatchArtifact = serde_json::from_str(&txt)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let Some(leaf) = artifact.leaves.get(leaf_index as usize) else {
+                continue;
+            };
+            if leaf.proof_hash != wanted {
+                continue; // entrada órfã de um batch que não foi fechado
+            }
+
+            return Ok(Some(InclusionProof {
+                proof_hash: wanted,
+                batch_id,
+                leaf_index,
+                seq: leaf.seq,
+                root_hash: artifact.root_hash,
+                path: leaf.path.clone(),
+                anchor_state: None,
+                settlement: None,
+                batch_cid: None,
+            }));
+        }
+
+        Ok(None)
+    }
+}
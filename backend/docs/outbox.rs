@@ -0,0 +1,273 @@
+// =========================================================
+// 📬 OUTBOX — jobs de ancoragem duráveis (um por batch)
+// =========================================================
+//
+// Estados: pending → submitted → confirmed
+//                 ↘ failed (tentativas esgotadas)
+//
+// Cada transição grava um snapshot do job (JSONL + fsync). No open(),
+// o último snapshot de cada job vence e o arquivo é compactado. Uma
+// última linha sem '\n' é um append rasgado e é descartada; qualquer
+// outra linha inválida impede a abertura (compactar apagaria o job).
+// O id do job é o batch_id: existe desde a primeira prova do batch.
+//
+// Um job `submitted` que não chega à finalidade em FINALITY_DEADLINE_SECS
+// volta para `fail_attempt` e é reenviado.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::settlement::SettlementReceipt;
+
+const BACKOFF_BASE_SECS: i64 = 2;
+const BACKOFF_MAX_SECS: i64 = 600;
+
+/// Prazo entre o envio da tx e a finalidade.
+pub const FINALITY_DEADLINE_SECS: i64 = 900;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    /// batch aberto, ou fechado aguardando (re)envio
+    Pending,
+    /// tx aceita pela chain, aguardando finalidade
+    Submitted,
+    /// finalidade confirmada pelo adaptador
+    Confirmed,
+    /// tentativas esgotadas — precisa de operador
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AnchorJob {
+    pub id: u64,
+    pub state: JobState,
+    /// `None` enquanto o batch está aberto
+    pub root_hash: Option<String>,
+    pub batch_cid: Option<String>,
+    pub receipt: Option<SettlementReceipt>,
+    pub attempts: u32,
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl AnchorJob {
+    fn new(id: u64) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id,
+            state: JobState::Pending,
+            root_hash: None,
+            batch_cid: None,
+            receipt: None,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Registra uma falha e agenda a próxima tentativa (backoff exponencial).
+    pub fn fail_attempt(&mut self, err: String, max_attempts: u32) {
+        self.attempts += 1;
+        self.last_error = Some(err);
+        if self.attempts >= max_attempts {
+            self.state = JobState::Failed;
+        } else {
+            self.state = JobState::Pending;
+            self.next_attempt_at = chrono::Utc::now().timestamp() + backoff_secs(self.attempts);
+        }
+    }
+
+    /// `submitted` há mais de FINALITY_DEADLINE_SECS sem finalidade.
+    pub fn finality_overdue(&self, now: i64) -> bool {
+        self.state == JobState::Submitted
+            && self
+                .receipt
+                .as_ref()
+                .is_some_and(|r| now - r.submitted_at > FINALITY_DEADLINE_SECS)
+    }
+}
+
+pub fn backoff_secs(attempts: u32) -> i64 {
+    BACKOFF_BASE_SECS
+        .saturating_mul(1i64 << attempts.min(20))
+        .min(BACKOFF_MAX_SECS)
+}
+
+pub struct Outbox {
+    path: PathBuf,
+    jobs: BTreeMap<u64, AnchorJob>,
+    pub max_attempts: u32,
+}
+
+impl Outbox {
+    pub fn open(path: &str, max_attempts: u32) -> io::Result<Self> {
+        let path = PathBuf::from(path);
+        if let Some(p) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(p)?;
+        }
+
+        let mut jobs = BTreeMap::new();
+        if path.exists() {
+            let txt = fs::read_to_string(&path)?;
+            let completo = txt.ends_with('\n');
+            let linhas: Vec<&str> = txt.lines().collect();
+            for (i, line) in linhas.iter().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<AnchorJob>(line.trim()) {
+                    Ok(job) => {
+                        jobs.insert(job.id, job);
+                    }
+                    // só a última linha, sem '\n', pode estar rasgada
+                    Err(_) if i + 1 == linhas.len() && !completo => {
+                        eprintln!("🩹 outbox: snapshot rasgado no fim de {:?} descartado", path);
+                    }
+                    Err(e) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("{:?} corrompido na linha {}: {}", path, i + 1, e),
+                        ))
+                    }
+                }
+            }
+        }
+
+        let outbox = Self { path, jobs, max_attempts: max_attempts.max(1) };
+        outbox.compact()?;
+        Ok(outbox)
+    }
+
+    fn compact(&self) -> io::Result<()> {
+        let tmp = self.path.with_extension("jsonl.tmp");
+        let mut f = File::create(&tmp)?;
+        for job in self.jobs.values() {
+            writeln!(f, "{}", serde_json::to_string(job)?)?;
+        }
+        f.sync_all()?;
+        fs::rename(&tmp, &self.path)
+    }
+
+    pub fn get(&self, id: u64) -> Option<&AnchorJob> {
+        self.jobs.get(&id)
+    }
+
+    /// Cria o job do batch se ainda não existe.
+    pub fn ensure(&mut self, id: u64) -> io::Result<()> {
+        if self.jobs.contains_key(&id) {
+            return Ok(());
+        }
+        self.save(AnchorJob::new(id))
+    }
+
+    pub fn save(&mut self, mut job: AnchorJob) -> io::Result<()> {
+        job.updated_at = chrono::Utc::now().timestamp();
+
+        let mut f = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(f, "{}", serde_json::to_string(&job)?)?;
+        f.sync_data()?;
+
+        self.jobs.insert(job.id, job);
+        Ok(())
+    }
+
+    /// Jobs `pending`/`submitted` cujo próximo passo já venceu.
+    pub fn due(&self, now: i64) -> Vec<AnchorJob> {
+        self.jobs
+            .values()
+            .filter(|j| matches!(j.state, JobState::Pending | JobState::Submitted))
+            .filter(|j| j.next_attempt_at <= now)
+            .cloned()
+            .collect()
+    }
+
+    /// Adia a próxima consulta de finalidade sem gravar snapshot
+    /// (polls sem mudança de estado não precisam ser duráveis).
+    pub fn defer(&mut self, id: u64, at: i64) {
+        if let Some(job) = self.jobs.get_mut(&id) {
+            job.next_attempt_at = at;
+        }
+    }
+
+    pub fn jobs(&self) -> impl Iterator<Item = &AnchorJob> {
+        self.jobs.values()
+    }
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caminho(nome: &str) -> String {
+        let mut p = std::env::temp_dir();
+        p.push(format!("outbox_{}_{}.jsonl", std::process::id(), nome));
+        let _ = fs::remove_file(&p);
+        p.to_string_lossy().into_owned()
+    }
+
+    fn com_dois_jobs(nome: &str) -> (String, String) {
+        let p = caminho(nome);
+        let mut o = Outbox::open(&p, 3).unwrap();
+        o.ensure(0).unwrap();
+        o.ensure(1).unwrap();
+        let txt = fs::read_to_string(&p).unwrap();
+        (p, txt)
+    }
+
+    #[test]
+    fn cauda_rasgada_descartada() {
+        let (p, mut txt) = com_dois_jobs("cauda");
+        txt.push_str("{\"id\":2,\"state\":\"pen");
+        fs::write(&p, txt).unwrap();
+
+        let o = Outbox::open(&p, 3).unwrap();
+        assert_eq!(o.jobs().count(), 2);
+        assert!(fs::read_to_string(&p).unwrap().ends_with('\n'));
+        let _ = fs::remove_file(&p);
+    }
+
+    #[test]
+    fn corrupcao_no_meio_recusada() {
+        let (p, txt) = com_dois_jobs("meio");
+        let original = txt.replacen("\"id\":0", "\"id\":x", 1);
+        fs::write(&p, &original).unwrap();
+
+        let err = Outbox::open(&p, 3).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // o arquivo fica como estava para o operador olhar
+        assert_eq!(fs::read_to_string(&p).unwrap(), original);
+        let _ = fs::remove_file(&p);
+    }
+
+    #[test]
+    fn submetido_sem_finalidade_vence() {
+        let mut job = AnchorJob::new(7);
+        let agora = chrono::Utc::now().timestamp();
+        job.state = JobState::Submitted;
+        job.receipt = Some(SettlementReceipt {
+            chain: "mock".into(),
+            tx_ref: "aa".into(),
+            submitted_at: agora,
+            valid_until: None,
+        });
+
+        assert!(!job.finality_overdue(agora + FINALITY_DEADLINE_SECS));
+        assert!(job.finality_overdue(agora + FINALITY_DEADLINE_SECS + 1));
+
+        job.fail_attempt("sem finalidade".into(), 3);
+        assert_eq!(job.state, JobState::Pending);
+        assert!(!job.finality_overdue(agora + FINALITY_DEADLINE_SECS + 1));
+    }
+}
@@ -0,0 +1,212 @@
+// =========================================================
+// 🛡️ REPLAY REGISTRY — anti-replay persistente
+// =========================================================
+//
+// Layout em disco: 256 buckets (`xx.bin`, xx = primeiro byte do hash
+// em hex), cada um um append-only de registros fixos de 32 bytes.
+// Em memória fica só um bloom filter de tamanho fixo: um "não" do
+// bloom é definitivo; um "talvez" é confirmado lendo um único bucket.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+const RECORD_LEN: usize = 32;
+const BLOOM_HASHES: u64 = 7;
+
+/// 2^23 bits = 1 MiB de RAM — ~1% de falso positivo até ~870k provas.
+pub const DEFAULT_BLOOM_BITS: u64 = 1 << 23;
+
+// =========================
+// BLOOM FILTER
+// =========================
+
+struct Bloom {
+    bits: Vec<u64>,
+    m: u64,
+}
+
+impl Bloom {
+    fn new(m: u64) -> Self {
+        let m = m.max(64);
+        Self {
+            bits: vec![0u64; m.div_ceil(64) as usize],
+            m,
+        }
+    }
+
+    // O item já é keccak256 → usa double hashing direto nos bytes.
+    fn positions(&self, h: &[u8; 32]) -> impl Iterator<Item = u64> + '_ {
+        let h1 = u64::from_le_bytes(h[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(h[8..16].try_into().unwrap()) | 1;
+        (0..BLOOM_HASHES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.m)
+    }
+
+    fn insert(&mut self, h: &[u8; 32]) {
+        let pos: Vec<u64> = self.positions(h).collect();
+        for p in pos {
+            self.bits[(p / 64) as usize] |= 1 << (p % 64);
+        }
+    }
+
+    fn maybe_contains(&self, h: &[u8; 32]) -> bool {
+        self.positions(h)
+            .all(|p| self.bits[(p / 64) as usize] & (1 << (p % 64)) != 0)
+    }
+}
+
+// =========================
+// REGISTRY
+// =========================
+
+pub struct ReplayRegistry {
+    dir: PathBuf,
+    bloom: Bloom,
+    total: u64,
+}
+
+impl ReplayRegistry {
+    /// Abre o diretório, descarta caudas rasgadas (crash no meio de um
+    /// registro) e reconstrói o bloom a partir dos buckets.
+    pub fn open(dir: &str, bloom_bits: u64) -> io::Result<Self> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir)?;
+
+        let mut bloom = Bloom::new(bloom_bits);
+        let mut total = 0u64;
+
+        for b in 0..=255u8 {
+            let path = dir.join(format!("{:02x}.bin", b));
+            if !path.exists() {
+                continue;
+            }
+
+            let mut data = Vec::new();
+            File::open(&path)?.read_to_end(&mut data)?;
+
+            let torn = data.len() % RECORD_LEN;
+            if torn != 0 {
+                eprintln!("⚠️  replay: cauda rasgada em {:?} ({} bytes) — truncando", path, torn);
+                let keep = (data.len() - torn) as u64;
+                OpenOptions::new().write(true).open(&path)?.set_len(keep)?;
+                data.truncate(keep as usize);
+            }
+
+            for rec in data.chunks_exact(RECORD_LEN) {
+                bloom.insert(rec.try_into().unwrap());
+                total += 1;
+            }
+        }
+
+        Ok(Self { dir, bloom, total })
+    }
+
+    fn bucket(&self, h: &[u8; 32]) -> PathBuf {
+        self.dir.join(format!("{:02x}.bin", h[0]))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.total
+    }
+
+    pub fn contains(&self, h: &[u8; 32]) -> io::Result<bool> {
+        if !self.bloom.maybe_contains(h) {
+            return Ok(false);
+        }
+
+        let path = self.bucket(h);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let mut data = Vec::new();
+        File::open(&path)?.read_to_end(&mut data)?;
+        Ok(data.chunks_exact(RECORD_LEN).any(|rec| rec == h))
+    }
+
+    /// Registra o hash de forma durável (write + fsync) antes de retornar.
+    /// Retorna `false` se o hash já tinha sido usado.
+    pub fn insert(&mut self, h: &[u8; 32]) -> io::Result<bool> {
+        if self.contains(h)? {
+            return Ok(false);
+        }
+
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.bucket(h))?;
+
+        // um único write de 32 bytes; se rasgar, o open() trunca
+        f.write_all(h)?;
+        f.sync_data()?;
+
+        self.bloom.insert(h);
+        self.total += 1;
+        Ok(true)
+    }
+}
+
+// =========================
+// TESTES
+// =========================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(nome: &str) -> String {
+        let mut p = std::env::temp_dir();
+        p.push(format!("replay_{}_{}", std::process::id(), nome));
+        let _ = fs::remove_dir_all(&p);
+        p.to_string_lossy().into_owned()
+    }
+
+    fn hash(i: u32) -> [u8; 32] {
+        let mut h = [0u8; 32];
+        h[0] = 0x42;
+        h[4..8].copy_from_slice(&i.to_le_bytes());
+        h[8..12].copy_from_slice(&i.wrapping_mul(2_654_435_761).to_le_bytes());
+        h
+    }
+
+    #[test]
+    fn falso_positivo_do_bloom_confere_o_bucket() {
+        let d = dir("bloom");
+        // bloom mínimo (64 bits): satura com poucas inserções
+        let mut reg = ReplayRegistry::open(&d, 64).unwrap();
+        for i in 0..64 {
+            assert!(reg.insert(&hash(i)).unwrap());
+        }
+
+        let novo = hash(1_000);
+        assert!(reg.bloom.maybe_contains(&novo), "bloom devia dizer talvez");
+        assert!(!reg.contains(&novo).unwrap());
+        assert!(reg.insert(&novo).unwrap());
+        assert!(!reg.insert(&novo).unwrap(), "replay aceito");
+        let _ = fs::remove_dir_all(&d);
+    }
+
+    #[test]
+    fn cauda_rasgada_do_bucket_e_truncada() {
+        let d = dir("rasgada");
+        let mut reg = ReplayRegistry::open(&d, DEFAULT_BLOOM_BITS).unwrap();
+        reg.insert(&hash(1)).unwrap();
+
+        // crash no meio do registro seguinte
+        let bucket = reg.bucket(&hash(2));
+        OpenOptions::new().append(true).open(&bucket).unwrap().write_all(&hash(2)[..5]).unwrap();
+
+        let mut reg = ReplayRegistry::open(&d, DEFAULT_BLOOM_BITS).unwrap();
+        assert_eq!(reg.len(), 1);
+        assert_eq!(fs::metadata(&bucket).unwrap().len(), RECORD_LEN as u64);
+        assert!(reg.contains(&hash(1)).unwrap());
+        assert!(!reg.contains(&hash(2)).unwrap());
+
+        // o registro seguinte começa alinhado
+        assert!(reg.insert(&hash(2)).unwrap());
+        let reg = ReplayRegistry::open(&d, DEFAULT_BLOOM_BITS).unwrap();
+        assert_eq!(reg.len(), 2);
+        assert!(reg.contains(&hash(2)).unwrap());
+        let _ = fs::remove_dir_all(&d);
+    }
+}
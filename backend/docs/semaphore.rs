@@ -1,34 +1,25 @@
-use warp::Filter;
+use warp::{Filter, Reply};
+use warp::http::StatusCode;
 use serde::Serialize;
 use bytes::Bytes;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
-use std::collections::HashSet;
-use tiny_keccak::{Hasher, Keccak};
 use serde_json::json;
-use reqwest::Client;
 
-// =========================
-// CONSTANTES
-// =========================
-
-const PINATA_JSON_ENDPOINT: &str =
-    "https://api.pinata.cloud/pinning/pinJSONToIPFS";
-
-const AMA_RPC_ENDPOINT: &str =
-"https://rpc.amadeus.network:26657";
-
-// =========================
-// HELPERS
-// =========================
+mod replay_registry;
+mod batcher;
+mod inclusion;
+mod settlement;
+mod outbox;
+use replay_registry::{ReplayRegistry, DEFAULT_BLOOM_BITS};
+use batcher::{BatchArtifact, Batcher};
+use settlement::{adapter_from_config, Commitment, SettlementAdapter, TxStatus};
+use outbox::{AnchorJob, JobState, Outbox, FINALITY_DEADLINE_SECS};
+use terra_dourada_core::artifact_store::{router_from_config, ArtifactKind, StoreRouter};
+use terra_dourada_core::config::{self, Service};
+use terra_dourada_core::crypto::keccak256;
 
-fn keccak256(data: &[u8]) -> [u8; 32] {
-    let mut hasher = Keccak::v256();
-    let mut out = [0u8; 32];
-    hasher.update(data);
-    hasher.finalize(&mut out);
-    out
-}
 
 // =========================
 // API RESPONSE
@@ -38,7 +29,27 @@ fn keccak256(data: &[u8]) -> [u8; 32] {
 struct ApiResponse {
     success: bool,
     message: String,
-    root_hash: Option<String>,
+    seq: Option<u64>,
+    job_id: Option<u64>,
+}
+
+/// Recusa do POST /submit_proof: mesmo corpo, status HTTP de verdade.
+fn submit_error(status: StatusCode, message: &str) -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&ApiResponse {
+            success: false,
+            message: message.into(),
+            seq: None,
+            job_id: None,
+        }),
+        status,
+    ).into_response()
+}
+
+#[derive(Serialize)]
+struct SeenResponse {
+    hash: String,
+    seen: bool,
 }
 
 // =========================
@@ -46,104 +57,245 @@ struct ApiResponse {
 // =========================
 
 pub struct Semaphore {
-    pub used_proof_hashes: HashSet<[u8; 32]>,
+    pub used_proof_hashes: ReplayRegistry,
+    pub batcher: Batcher,
+    pub outbox: Outbox,
+    pub settlement: Arc<dyn SettlementAdapter>,
+    pub stores: StoreRouter,
 }
 
 impl Semaphore {
-    pub fn new() -> Self {
-        Self {
-            used_proof_hashes: HashSet::new(),
+    pub fn open(
+        replay_dir: &str,
+        batcher: Batcher,
+        outbox_path: &str,
+        max_attempts: u32,
+        settlement: Arc<dyn SettlementAdapter>,
+        stores: StoreRouter,
+    ) -> std::io::Result<Self> {
+        let mut sem = Self {
+            used_proof_hashes: ReplayRegistry::open(replay_dir, DEFAULT_BLOOM_BITS)?,
+            batcher,
+            outbox: Outbox::open(outbox_path, max_attempts)?,
+            settlement,
+            stores,
+        };
+        sem.reconcile_jobs()?;
+        sem.reconcile_replay()?;
+        Ok(sem)
+    }
+
+    /// Crash entre `batcher.push()` e o registro anti-replay: a prova está
+    /// no journal mas o hash não. Registra de novo (insert é idempotente).
+    fn reconcile_replay(&mut self) -> std::io::Result<()> {
+        let hashes: Vec<[u8; 32]> = self
+            .batcher
+            .pending()
+            .iter()
+            .filter_map(|l| hex::decode(&l.proof_hash).ok().and_then(|b| b.try_into().ok()))
+            .collect();
+        for h in &hashes {
+            self.used_proof_hashes.insert(h)?;
         }
+        Ok(())
     }
-}
 
-// =========================
-// AMA TRANSACTION STRUCT
-// =========================
+    /// Crash entre `batcher.close()` e o snapshot do job: o artefato existe
+    /// mas o job ficou sem root. Completa a partir do disco.
+    fn reconcile_jobs(&mut self) -> std::io::Result<()> {
+        let open_id = self.batcher.open_batch_id();
+        let orphans: Vec<AnchorJob> = self
+            .outbox
+            .jobs()
+            .filter(|j| j.state == JobState::Pending && j.root_hash.is_none() && j.id < open_id)
+            .cloned()
+            .collect();
+
+        for mut job in orphans {
+            match self.batcher.artifact(job.id)? {
+                Some(artifact) => job.root_hash = Some(artifact.root_hash),
+                None => {
+                    job.state = JobState::Failed;
+                    job.last_error = Some("artefato do batch não encontrado".into());
+                }
+            }
+            self.outbox.save(job)?;
+        }
+        Ok(())
+    }
 
-#[derive(Serialize)]
-struct AmaTx {
-    from: String,
-    payload: String,
-    timestamp: i64,
+    /// Fecha o batch aberto (se houver) e libera o job para o worker.
+    fn close_batch(&mut self) -> std::io::Result<()> {
+        let operator = self.settlement.operator().to_string();
+        let Some(artifact) = self.batcher.close(&operator)? else {
+            return Ok(());
+        };
+
+        println!(
+            "🌳 Batch #{} fechado: seq {}..={} → root {}",
+            artifact.batch_id, artifact.first_seq, artifact.last_seq, artifact.root_hash
+        );
+
+        self.outbox.ensure(artifact.batch_id)?;
+        if let Some(mut job) = self.outbox.get(artifact.batch_id).cloned() {
+            job.root_hash = Some(artifact.root_hash);
+            job.next_attempt_at = chrono::Utc::now().timestamp();
+            self.outbox.save(job)?;
+        }
+        Ok(())
+    }
 }
 
 // =========================
-// SEND ROOT HASH → AMA
+// UPLOAD BATCH → ARTIFACT STORE
 // =========================
 
-async fn send_to_ama(
-    root_hex: &str,
-    operator: &str,
-) {
-    println!("🟡 Enviando root hash → AMA");
-
-    let tx = AmaTx {
-        from: operator.to_string(),
-        payload: root_hex.to_string(),
-        timestamp: chrono::Utc::now().timestamp(),
+/// Retorna o CID (ou tx id, no Arweave) do artefato guardado.
+async fn upload_batch(
+    stores: &StoreRouter,
+    artifact: &BatchArtifact,
+) -> Option<String> {
+    println!(
+        "📦 Enviando batch #{} → {} ({} folhas)",
+        artifact.batch_id, stores.batches.name(), artifact.leaf_count
+    );
+
+    let bytes = match serde_json::to_vec(artifact) {
+        Ok(b) => b,
+        Err(e) => {
+            println!("❌ Erro ao serializar batch: {:?}", e);
+            return None;
+        }
     };
 
-    let client = Client::new();
+    let label = format!("terra_dourada_batch_{:08}.json", artifact.batch_id);
 
-    let resp = client
-        .post(AMA_RPC_ENDPOINT)
-        .json(&json!({
-            "method": "broadcast_tx",
-            "params": tx
-        }))
-        .send()
-        .await;
-
-    match resp {
-        Ok(r) => {
-            let txt = r.text().await.unwrap_or_default();
-            println!("✅ AMA respondeu: {}", txt);
+    match stores.put(ArtifactKind::Batch, &bytes, &label).await {
+        Ok(stored) => {
+            println!("📦 {} guardou batch → {}", stored.store, stored.id);
+            Some(stored.id)
         }
         Err(e) => {
-            println!("❌ Erro ao enviar para AMA: {:?}", e);
+            println!("❌ Erro ao guardar batch: {}", e);
+            None
         }
     }
 }
 
 // =========================
-// UPLOAD BATCH → PINATA
+// WORKER DO OUTBOX → IPFS + CHAIN
 // =========================
 
-async fn upload_batch_to_pinata(
-    root_hex: &str,
-    proof_bytes_hex: &str,
-    operator: &str,
-    jwt: &str,
-) {
-    println!("📦 Enviando batch → Pinata");
-
-    let payload = json!({
-        "type": "terra_dourada_batch",
-        "root_hash": root_hex,
-        "proof_bytes_hex": proof_bytes_hex,
-        "operator": operator,
-        "timestamp": chrono::Utc::now().timestamp()
-    });
-
-    let client = Client::new();
+const FINALITY_POLL_SECS: i64 = 10;
 
-    let resp = client
-        .post(PINATA_JSON_ENDPOINT)
-        .header("Authorization", format!("Bearer {}", jwt))
-        .json(&payload)
-        .send()
-        .await;
-
-    match resp {
-        Ok(r) => {
-            let txt = r.text().await.unwrap_or_default();
-            println!("📦 Pinata respondeu: {}", txt);
+/// Um passo de cada job vencido. O lock só é segurado para ler e gravar
+/// snapshots — upload e RPC rodam sem travar o /submit_proof.
+async fn drive_outbox(semaphore: &Arc<Mutex<Semaphore>>) -> std::io::Result<()> {
+    let (jobs, settlement, stores, max_attempts) = {
+        let mut sem = semaphore.lock().await;
+        if sem.batcher.should_close() {
+            sem.close_batch()?;
         }
-        Err(e) => {
-            println!("❌ Erro ao enviar Pinata: {:?}", e);
+        let now = chrono::Utc::now().timestamp();
+        let jobs: Vec<AnchorJob> = sem
+            .outbox
+            .due(now)
+            .into_iter()
+            .filter(|j| j.root_hash.is_some()) // batch ainda aberto
+            .collect();
+        (jobs, sem.settlement.clone(), sem.stores.clone(), sem.outbox.max_attempts)
+    };
+
+    for mut job in jobs {
+        match job.state {
+            JobState::Pending => {
+                // artefato fora do lock: é imutável depois do close
+                if job.batch_cid.is_none() {
+                    let artifact = semaphore.lock().await.batcher.artifact(job.id)?;
+                    let Some(artifact) = artifact else {
+                        job.fail_attempt("artefato do batch não encontrado".into(), max_attempts);
+                        semaphore.lock().await.outbox.save(job)?;
+                        continue;
+                    };
+                    match upload_batch(&stores, &artifact).await {
+                        Some(cid) => job.batch_cid = Some(cid),
+                        None => {
+                            job.fail_attempt("falha ao guardar artefato do batch".into(), max_attempts);
+                            semaphore.lock().await.outbox.save(job)?;
+                            continue;
+                        }
+                    }
+                }
+
+                println!("🟡 Job #{} → {} (tentativa {})", job.id, settlement.chain(), job.attempts + 1);
+
+                let commitment = Commitment {
+                    batch_id: job.id,
+                    root_hash: job.root_hash.clone().unwrap_or_default(),
+                    batch_cid: job.batch_cid.clone(),
+                };
+
+                match settlement.submit(&commitment).await {
+                    Ok(receipt) => {
+                        println!("📨 Job #{} submetido: {} tx = {}", job.id, receipt.chain, receipt.tx_ref);
+                        job.state = JobState::Submitted;
+                        job.receipt = Some(receipt);
+                        job.last_error = None;
+                        job.next_attempt_at = chrono::Utc::now().timestamp() + FINALITY_POLL_SECS;
+                    }
+                    Err(e) => {
+                        println!("❌ Job #{} — erro no settlement ({}): {}", job.id, settlement.chain(), e);
+                        job.fail_attempt(e.to_string(), max_attempts);
+                    }
+                }
+                semaphore.lock().await.outbox.save(job)?;
+            }
+
+            JobState::Submitted => {
+                let Some(receipt) = job.receipt.clone() else {
+                    job.fail_attempt("job submetido sem recibo".into(), max_attempts);
+                    semaphore.lock().await.outbox.save(job)?;
+                    continue;
+                };
+
+                let next_poll = chrono::Utc::now().timestamp() + FINALITY_POLL_SECS;
+
+                match settlement.status(&receipt).await {
+                    Ok(TxStatus::Finalized { height }) => {
+                        let altura = height.map_or_else(|| "?".to_string(), |h| h.to_string());
+                        println!("✅ Job #{} confirmado ({} altura {})", job.id, receipt.chain, altura);
+                        job.state = JobState::Confirmed;
+                        semaphore.lock().await.outbox.save(job)?;
+                    }
+                    Ok(TxStatus::Failed { reason }) => {
+                        println!("❌ Job #{} falhou na chain: {}", job.id, reason);
+                        job.receipt = None;
+                        job.fail_attempt(reason, max_attempts);
+                        semaphore.lock().await.outbox.save(job)?;
+                    }
+                    status => {
+                        if let Err(e) = &status {
+                            // erro de consulta não invalida a tx: tenta de novo depois
+                            println!("⚠️ Job #{} — falha ao consultar status: {}", job.id, e);
+                        }
+                        if job.finality_overdue(chrono::Utc::now().timestamp()) {
+                            let motivo = format!("sem finalidade {}s após o envio", FINALITY_DEADLINE_SECS);
+                            println!("⏰ Job #{} — {}: reenviando", job.id, motivo);
+                            job.receipt = None;
+                            job.fail_attempt(motivo, max_attempts);
+                            semaphore.lock().await.outbox.save(job)?;
+                        } else {
+                            semaphore.lock().await.outbox.defer(job.id, next_poll);
+                        }
+                    }
+                }
+            }
+
+            JobState::Confirmed | JobState::Failed => {}
         }
     }
+
+    Ok(())
 }
 
 // =========================
@@ -156,12 +308,6 @@ fn with_semaphore(
     warp::any().map(move || sem.clone())
 }
 
-fn with_jwt(
-    jwt: String,
-) -> impl Filter<Extract = (String,), Error = std::convert::Infallible> + Clone {
-    warp::any().map(move || jwt.clone())
-}
-
 // =========================
 // MAIN HANDLER
 // =========================
@@ -169,54 +315,173 @@ fn with_jwt(
 async fn handle_submit(
     proof_bytes: Bytes,
     semaphore: Arc<Mutex<Semaphore>>,
-    pinata_jwt: String,
-) -> Result<impl warp::Reply, warp::Rejection> {
+) -> Result<warp::reply::Response, warp::Rejection> {
 
     println!("📥 Prova recebida: {} bytes", proof_bytes.len());
 
     let mut sem = semaphore.lock().await;
 
-    // 1️⃣ Hash determinístico
+    // 1️⃣ Hash determinístico (folha do batch)
     let proof_hash = keccak256(&proof_bytes);
-    let root_hex = hex::encode(proof_hash);
+    let proof_hash_hex = hex::encode(proof_hash);
 
-    println!("🔹 Root hash: {}", root_hex);
+    println!("🔹 Proof hash: {}", proof_hash_hex);
 
-    // 2️⃣ Anti-replay
-    if sem.used_proof_hashes.contains(&proof_hash) {
-        return Ok(warp::reply::json(&ApiResponse {
-            success: false,
-            message: "Prova já utilizada".into(),
-            root_hash: None,
-        }));
+    // 2️⃣ Anti-replay: consulta agora, registra só depois de enfileirar
+    match sem.used_proof_hashes.contains(&proof_hash) {
+        Ok(false) => {}
+        Ok(true) => {
+            return Ok(submit_error(StatusCode::CONFLICT, "Prova já utilizada"));
+        }
+        Err(e) => {
+            println!("❌ Erro no registro anti-replay: {:?}", e);
+            return Ok(submit_error(StatusCode::INTERNAL_SERVER_ERROR, "Falha ao registrar prova"));
+        }
     }
 
-    sem.used_proof_hashes.insert(proof_hash);
-
-    // 3️⃣ Enviar → AMA
-    let operator = "HFZhLLhNWZyuqT2YpraFWhMRjQtAMwsfPA3PiGQBNw6D";
+    // 3️⃣ Enfileirar no batch aberto
+    let seq = match sem.batcher.push(&proof_hash, &proof_bytes) {
+        Ok(seq) => seq,
+        Err(e) => {
+            println!("❌ Erro ao enfileirar prova: {:?}", e);
+            return Ok(submit_error(StatusCode::INTERNAL_SERVER_ERROR, "Falha ao enfileirar prova"));
+        }
+    };
 
-    send_to_ama(
-        &root_hex,
-        operator,
-    ).await;
+    // 4️⃣ Job de ancoragem do batch aberto (o worker envia quando fechar)
+    let job_id = sem.batcher.open_batch_id();
+    if let Err(e) = sem.outbox.ensure(job_id) {
+        println!("❌ Erro ao registrar job de ancoragem: {:?}", e);
+        if let Err(e) = sem.batcher.discard(seq) {
+            println!("❌ Erro ao desfazer seq {}: {:?}", seq, e);
+        }
+        return Ok(submit_error(StatusCode::INTERNAL_SERVER_ERROR, "Falha ao registrar job de ancoragem"));
+    }
 
-    // 4️⃣ Enviar batch → IPFS
-    let proof_bytes_hex = hex::encode(&proof_bytes);
+    // 5️⃣ Hash usado gravado em disco só com a prova já no journal; se
+    //    falhar, a prova sai do batch e pode ser reenviada
+    if let Err(e) = sem.used_proof_hashes.insert(&proof_hash) {
+        println!("❌ Erro no registro anti-replay: {:?}", e);
+        if let Err(e) = sem.batcher.discard(seq) {
+            println!("❌ Erro ao desfazer seq {}: {:?}", seq, e);
+        }
+        return Ok(submit_error(StatusCode::INTERNAL_SERVER_ERROR, "Falha ao registrar prova"));
+    }
 
-    upload_batch_to_pinata(
-        &root_hex,
-        &proof_bytes_hex,
-        operator,
-        &pinata_jwt,
-    ).await;
+    // 6️⃣ Batch cheio → fecha agora; upload e chain ficam com o worker
+    if sem.batcher.should_close() {
+        if let Err(e) = sem.close_batch() {
+            println!("❌ Erro ao fechar batch: {:?}", e);
+        }
+    }
 
-    // 5️⃣ OK
+    // 7️⃣ Aceita — ancoragem acompanhada em GET /jobs/{id}
     Ok(warp::reply::json(&ApiResponse {
         success: true,
-        message: "Prova ancorada em AMA + IPFS".into(),
-        root_hash: Some(root_hex),
-    }))
+        message: "Prova enfileirada; ancoragem pendente".into(),
+        seq: Some(seq),
+        job_id: Some(job_id),
+    }).into_response())
+}
+
+// =========================
+// GET /seen/{hash}
+// =========================
+
+async fn handle_seen(
+    hash_hex: String,
+    semaphore: Arc<Mutex<Semaphore>>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+
+    let Some(hash) = hex::decode(hash_hex.trim())
+        .ok()
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+    else {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&json!({ "error": "hash deve ter 32 bytes em hex" })),
+            StatusCode::BAD_REQUEST,
+        ).into_response());
+    };
+
+    let sem = semaphore.lock().await;
+
+    match sem.used_proof_hashes.contains(&hash) {
+        Ok(seen) => Ok(warp::reply::json(&SeenResponse {
+            hash: hex::encode(hash),
+            seen,
+        }).into_response()),
+        Err(e) => {
+            println!("❌ Erro ao consultar anti-replay: {:?}", e);
+            Ok(warp::reply::with_status(
+                warp::reply::json(&json!({ "error": "falha ao consultar registro" })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).into_response())
+        }
+    }
+}
+
+// =========================
+// GET /inclusion/{hash}
+// =========================
+
+async fn handle_inclusion(
+    hash_hex: String,
+    semaphore: Arc<Mutex<Semaphore>>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+
+    let Some(hash) = hex::decode(hash_hex.trim())
+        .ok()
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+    else {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&json!({ "error": "hash deve ter 32 bytes em hex" })),
+            StatusCode::BAD_REQUEST,
+        ).into_response());
+    };
+
+    let sem = semaphore.lock().await;
+
+    match sem.batcher.index.inclusion(&hash) {
+        Ok(Some(mut proof)) => {
+            if let Some(job) = sem.outbox.get(proof.batch_id) {
+                proof.anchor_state = Some(job.state);
+                proof.settlement = job.receipt.clone();
+                proof.batch_cid = job.batch_cid.clone();
+            }
+            Ok(warp::reply::json(&proof).into_response())
+        }
+        Ok(None) => Ok(warp::reply::with_status(
+            warp::reply::json(&json!({ "error": "prova não está em nenhum batch fechado" })),
+            StatusCode::NOT_FOUND,
+        ).into_response()),
+        Err(e) => {
+            println!("❌ Erro ao montar prova de inclusão: {:?}", e);
+            Ok(warp::reply::with_status(
+                warp::reply::json(&json!({ "error": "falha ao consultar índice" })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ).into_response())
+        }
+    }
+}
+
+// =========================
+// GET /jobs/{id}
+// =========================
+
+async fn handle_job(
+    id: u64,
+    semaphore: Arc<Mutex<Semaphore>>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+
+    let sem = semaphore.lock().await;
+
+    match sem.outbox.get(id) {
+        Some(job) => Ok(warp::reply::json(job).into_response()),
+        None => Ok(warp::reply::with_status(
+            warp::reply::json(&json!({ "error": "job não encontrado" })),
+            StatusCode::NOT_FOUND,
+        ).into_response()),
+    }
 }
 
 // =========================
@@ -225,25 +490,108 @@ async fn handle_submit(
 
 #[tokio::main]
 async fn main() {
-    println!("🚀 Terra Dourada Semaphore — AMA + IPFS");
-    println!("🌐 Endpoint → POST /submit_proof");
-
-    let semaphore = Arc::new(Mutex::new(Semaphore::new()));
+    let cfg = config::load(Service::Semaphore);
 
-    let pinata_jwt =
-        std::env::var("PINATA_JWT")
-            .expect("❌ PINATA_JWT não definido");
+    println!("🚀 Terra Dourada Semaphore — settlement + artifact store (batch Merkle)");
+    println!("🌐 Endpoint → POST /submit_proof");
+    println!("🌐 Endpoint → GET  /seen/{{hash}}");
+    println!("🌐 Endpoint → GET  /inclusion/{{hash}}");
+    println!("🌐 Endpoint → GET  /jobs/{{id}}");
+
+    // [batch] do config.rs
+    let replay_dir = cfg.batch.replay_dir.clone();
+    let batch_dir = cfg.batch.dir.clone();
+    let batch_size = cfg.batch.size;
+    let batch_timeout = cfg.batch.timeout();
+    let outbox_path = cfg.batch.outbox();
+    let max_attempts = cfg.batch.anchor_max_attempts;
+
+    let settlement: Arc<dyn SettlementAdapter> = Arc::from(
+        adapter_from_config(&cfg.settlement, cfg.timeouts.settlement())
+            .unwrap_or_else(|e| panic!("❌ Settlement inválido: {}", e)),
+    );
+
+    println!("⛓️ Settlement: {} (operador {})", settlement.chain(), settlement.operator());
+
+    let stores = router_from_config(&cfg.storage, cfg.timeouts.store())
+        .unwrap_or_else(|e| panic!("❌ Artifact store inválido: {}", e));
+
+    println!("📦 Batches → {}", stores.batches.name());
+
+    let batcher = Batcher::open(&batch_dir, batch_size, batch_timeout)
+        .expect("❌ Falha ao abrir o batcher");
+
+    let semaphore = Semaphore::open(
+        &replay_dir,
+        batcher,
+        &outbox_path,
+        max_attempts,
+        settlement,
+        stores,
+    )
+    .expect("❌ Falha ao abrir estado do Semaphore");
+
+    println!("🛡️ Anti-replay: {} provas já usadas ({})", semaphore.used_proof_hashes.len(), replay_dir);
+    println!(
+        "🌳 Batch: até {} provas ou {}s — {} pendentes ({})",
+        batch_size, batch_timeout.as_secs(), semaphore.batcher.pending_len(), batch_dir
+    );
+    println!(
+        "📬 Outbox: {} jobs em aberto, até {} tentativas ({})",
+        semaphore.outbox.jobs().filter(|j| matches!(j.state, JobState::Pending | JobState::Submitted)).count(),
+        max_attempts,
+        outbox_path
+    );
+
+    let semaphore = Arc::new(Mutex::new(semaphore));
+
+    // ⏱️ fecha batches por timeout e move os jobs do outbox
+    {
+        let semaphore = semaphore.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                tick.tick().await;
+                if let Err(e) = drive_outbox(&semaphore).await {
+                    println!("❌ Erro no worker do outbox: {:?}", e);
+                }
+            }
+        });
+    }
 
-    let route =
+    let submit =
         warp::path("submit_proof")
             .and(warp::post())
             .and(warp::body::bytes())
-            .and(with_semaphore(semaphore))
-            .and(with_jwt(pinata_jwt))
+            .and(with_semaphore(semaphore.clone()))
             .and_then(handle_submit);
 
-    warp::serve(route)
-        .run(([127, 0, 0, 1], 3030))
-        .await;
+    let seen =
+        warp::path!("seen" / String)
+            .and(warp::get())
+            .and(with_semaphore(semaphore.clone()))
+            .and_then(handle_seen);
+
+    let inclusion =
+        warp::path!("inclusion" / String)
+            .and(warp::get())
+            .and(with_semaphore(semaphore.clone()))
+            .and_then(handle_inclusion);
+
+    let jobs =
+        warp::path!("jobs" / u64)
+            .and(warp::get())
+            .and(with_semaphore(semaphore))
+            .and_then(handle_job);
+
+    let routes = submit.or(seen).or(inclusion).or(jobs);
+    let listen = &cfg.semaphore;
+
+    println!("🌐 Semaphore rodando em {}", listen.url());
+
+    match &listen.tls {
+        Some(tls) => warp::serve(routes).tls().cert_path(&tls.cert).key_path(&tls.key).run(listen.addr()).await,
+        None => warp::serve(routes).run(listen.addr()).await,
+    }
 }
 
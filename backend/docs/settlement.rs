@@ -0,0 +1,527 @@
+// =========================================================
+// ⛓️ SETTLEMENT — adaptadores de chain (AMA, Solana, mock)
+// =========================================================
+//
+// O Semaphore só conhece `SettlementAdapter`: publica um `Commitment`
+// (raiz Merkle + CID do batch) e recebe um `SettlementReceipt` tipado,
+// que depois é consultado com `status` / `is_final`.
+//
+// Escolha via [settlement] backend = ama | solana | mock (padrão: ama),
+// ou TD_SETTLEMENT (config.rs).
+
+use async_trait::async_trait;
+use base64::engine::{general_purpose, Engine as _};
+use ed25519_dalek::{Signer, SigningKey};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use terra_dourada_core::config::SettlementConfig;
+use terra_dourada_core::crypto::keccak256;
+
+const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+
+/// AMA: tx que o nó ainda não conhece depois disso saiu do mempool.
+const AMA_NOT_FOUND_SECS: i64 = 120;
+
+// =========================
+// TIPOS
+// =========================
+
+/// O que vai para a chain: só o compromisso do batch.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Commitment {
+    pub batch_id: u64,
+    pub root_hash: String,
+    pub batch_cid: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SettlementReceipt {
+    pub chain: String,
+    pub tx_ref: String,
+    pub submitted_at: i64,
+    /// Solana: última altura de bloco em que a tx ainda pode entrar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum TxStatus {
+    /// a chain ainda não conhece a tx
+    Pending,
+    /// incluída, mas ainda pode ser revertida
+    Included { height: Option<u64> },
+    Finalized { height: Option<u64> },
+    Failed { reason: String },
+}
+
+#[derive(Debug)]
+pub enum SettlementError {
+    /// rede / timeout / HTTP
+    Transport(String),
+    /// a chain respondeu e recusou
+    Rejected(String),
+    /// resposta fora do formato esperado
+    Malformed(String),
+    Io(String),
+}
+
+impl fmt::Display for SettlementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettlementError::Transport(e) => write!(f, "transporte: {}", e),
+            SettlementError::Rejected(e) => write!(f, "rejeitada: {}", e),
+            SettlementError::Malformed(e) => write!(f, "resposta inválida: {}", e),
+            SettlementError::Io(e) => write!(f, "io: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SettlementError {}
+
+impl From<std::io::Error> for SettlementError {
+    fn from(e: std::io::Error) -> Self {
+        SettlementError::Io(e.to_string())
+    }
+}
+
+// =========================
+// TRAIT
+// =========================
+
+#[async_trait]
+pub trait SettlementAdapter: Send + Sync {
+    /// Nome curto da chain (vai no recibo).
+    fn chain(&self) -> &str;
+
+    /// Endereço/identidade que assina as txs.
+    fn operator(&self) -> &str;
+
+    async fn submit(&self, commitment: &Commitment) -> Result<SettlementReceipt, SettlementError>;
+
+    async fn status(&self, receipt: &SettlementReceipt) -> Result<TxStatus, SettlementError>;
+
+    async fn is_final(&self, receipt: &SettlementReceipt) -> Result<bool, SettlementError> {
+        Ok(matches!(self.status(receipt).await?, TxStatus::Finalized { .. }))
+    }
+}
+
+fn http_client(timeout: Duration) -> Client {
+    Client::builder()
+        .timeout(timeout)
+        .build()
+        .expect("❌ falha ao criar cliente HTTP")
+}
+
+/// POST JSON-RPC 2.0 → `result`, ou erro tipado.
+async fn json_rpc(client: &Client, url: &str, method: &str, params: Value) -> Result<Value, SettlementError> {
+    let resp = client
+        .post(url)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        }))
+        .send()
+        .await
+        .map_err(|e| SettlementError::Transport(e.to_string()))?;
+
+    let status = resp.status();
+    let txt = resp.text().await.map_err(|e| SettlementError::Transport(e.to_string()))?;
+
+    if !status.is_success() {
+        return Err(SettlementError::Transport(format!("HTTP {}: {}", status, txt)));
+    }
+
+    let body: Value = serde_json::from_str(&txt)
+        .map_err(|e| SettlementError::Malformed(format!("{}: {}", e, txt)))?;
+
+    if let Some(err) = body.get("error").filter(|e| !e.is_null()) {
+        return Err(SettlementError::Rejected(err.to_string()));
+    }
+
+    body.get("result")
+        .cloned()
+        .ok_or_else(|| SettlementError::Malformed(format!("sem result: {}", txt)))
+}
+
+// =========================================================
+// AMA (JSON-RPC estilo Tendermint)
+// =========================================================
+
+#[derive(Serialize)]
+struct AmaTx {
+    from: String,
+    payload: String,
+    timestamp: i64,
+}
+
+pub struct AmaAdapter {
+    endpoint: String,
+    operator: String,
+    client: Client,
+}
+
+impl AmaAdapter {
+    pub fn new(endpoint: String, operator: String, timeout: Duration) -> Self {
+        Self { endpoint, operator, client: http_client(timeout) }
+    }
+}
+
+#[async_trait]
+impl SettlementAdapter for AmaAdapter {
+    fn chain(&self) -> &str {
+        "ama"
+    }
+
+    fn operator(&self) -> &str {
+        &self.operator
+    }
+
+    async fn submit(&self, commitment: &Commitment) -> Result<SettlementReceipt, SettlementError> {
+        let tx = AmaTx {
+            from: self.operator.clone(),
+            payload: commitment.root_hash.clone(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+
+        let result = json_rpc(&self.client, &self.endpoint, "broadcast_tx", json!(tx)).await?;
+
+        // Tendermint: code != 0 → CheckTx recusou
+        if let Some(code) = result.get("code").and_then(|c| c.as_u64()).filter(|c| *c != 0) {
+            let log = result.get("log").and_then(|l| l.as_str()).unwrap_or_default();
+            return Err(SettlementError::Rejected(format!("code {}: {}", code, log)));
+        }
+
+        let tx_ref = result
+            .get("hash")
+            .and_then(|h| h.as_str())
+            .ok_or_else(|| SettlementError::Malformed(format!("sem hash: {}", result)))?;
+
+        Ok(SettlementReceipt {
+            chain: self.chain().into(),
+            tx_ref: tx_ref.to_string(),
+            submitted_at: tx.timestamp,
+            valid_until: None,
+        })
+    }
+
+    async fn status(&self, receipt: &SettlementReceipt) -> Result<TxStatus, SettlementError> {
+        let result = match json_rpc(&self.client, &self.endpoint, "tx", json!({ "hash": receipt.tx_ref })).await {
+            Ok(r) => r,
+            // nó ainda não indexou a tx — até AMA_NOT_FOUND_SECS
+            Err(SettlementError::Rejected(e)) => {
+                let idade = chrono::Utc::now().timestamp() - receipt.submitted_at;
+                if idade < AMA_NOT_FOUND_SECS {
+                    return Ok(TxStatus::Pending);
+                }
+                return Ok(TxStatus::Failed {
+                    reason: format!("tx não encontrada após {}s: {}", idade, e),
+                });
+            }
+            Err(e) => return Err(e),
+        };
+
+        let height = result
+            .get("height")
+            .and_then(|h| h.as_str().and_then(|s| s.parse().ok()).or_else(|| h.as_u64()));
+
+        match result.pointer("/tx_result/code").and_then(|c| c.as_u64()) {
+            Some(0) | None => Ok(TxStatus::Finalized { height }), // finalidade instantânea (BFT)
+            Some(code) => Ok(TxStatus::Failed {
+                reason: format!("DeliverTx code {}", code),
+            }),
+        }
+    }
+}
+
+// =========================================================
+// SOLANA (memo program via JSON-RPC)
+// =========================================================
+
+pub struct SolanaMemoAdapter {
+    endpoint: String,
+    signer: SigningKey,
+    operator: String,
+    client: Client,
+}
+
+impl SolanaMemoAdapter {
+    /// `keypair_path` no formato do solana-keygen (array JSON de 64 bytes).
+    pub fn from_keypair_file(endpoint: String, keypair_path: &str, timeout: Duration) -> Result<Self, String> {
+        let txt = fs::read_to_string(keypair_path)
+            .map_err(|e| format!("falha ao ler keypair {}: {}", keypair_path, e))?;
+        let bytes: Vec<u8> = serde_json::from_str(&txt)
+            .map_err(|e| format!("keypair inválida: {}", e))?;
+        if bytes.len() != 64 {
+            return Err(format!("keypair deve ter 64 bytes, tem {}", bytes.len()));
+        }
+
+        let signer = SigningKey::from_bytes(&bytes[..32].try_into().unwrap());
+        let operator = bs58::encode(signer.verifying_key().to_bytes()).into_string();
+
+        Ok(Self { endpoint, signer, operator, client: http_client(timeout) })
+    }
+
+    /// Mensagem legacy com uma única instrução Memo assinada pelo operador.
+    fn build_message(&self, blockhash: &[u8; 32], memo: &[u8]) -> Vec<u8> {
+        let memo_program = bs58::decode(MEMO_PROGRAM_ID).into_vec().unwrap();
+
+        let mut msg = Vec::new();
+        // header: 1 assinatura, 0 readonly assinadas, 1 readonly não assinada
+        msg.extend_from_slice(&[1, 0, 1]);
+        short_vec(2, &mut msg);
+        msg.extend_from_slice(&self.signer.verifying_key().to_bytes());
+        msg.extend_from_slice(&memo_program);
+        msg.extend_from_slice(blockhash);
+        // instruções
+        short_vec(1, &mut msg);
+        msg.push(1); // program_id_index → memo
+        short_vec(1, &mut msg);
+        msg.push(0); // operador como signer do memo
+        short_vec(memo.len(), &mut msg);
+        msg.extend_from_slice(memo);
+        msg
+    }
+}
+
+/// compact-u16 da Solana.
+fn short_vec(mut n: usize, out: &mut Vec<u8>) {
+    loop {
+        let mut b = (n & 0x7f) as u8;
+        n >>= 7;
+        if n != 0 {
+            b |= 0x80;
+        }
+        out.push(b);
+        if n == 0 {
+            break;
+        }
+    }
+}
+
+#[async_trait]
+impl SettlementAdapter for SolanaMemoAdapter {
+    fn chain(&self) -> &str {
+        "solana"
+    }
+
+    fn operator(&self) -> &str {
+        &self.operator
+    }
+
+    async fn submit(&self, commitment: &Commitment) -> Result<SettlementReceipt, SettlementError> {
+        let latest = json_rpc(
+            &self.client,
+            &self.endpoint,
+            "getLatestBlockhash",
+            json!([{ "commitment": "finalized" }]),
+        )
+        .await?;
+
+        let blockhash: [u8; 32] = latest
+            .pointer("/value/blockhash")
+            .and_then(|b| b.as_str())
+            .and_then(|b| bs58::decode(b).into_vec().ok())
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| SettlementError::Malformed(format!("blockhash: {}", latest)))?;
+        let valid_until = latest.pointer("/value/lastValidBlockHeight").and_then(|h| h.as_u64());
+
+        let memo = format!(
+            "terra_dourada:{}:{}:{}",
+            commitment.batch_id,
+            commitment.root_hash,
+            commitment.batch_cid.as_deref().unwrap_or("-")
+        );
+
+        let message = self.build_message(&blockhash, memo.as_bytes());
+        let signature = self.signer.sign(&message).to_bytes();
+
+        let mut tx = Vec::new();
+        short_vec(1, &mut tx);
+        tx.extend_from_slice(&signature);
+        tx.extend_from_slice(&message);
+
+        let result = json_rpc(
+            &self.client,
+            &self.endpoint,
+            "sendTransaction",
+            json!([general_purpose::STANDARD.encode(&tx), { "encoding": "base64" }]),
+        )
+        .await?;
+
+        let tx_ref = result
+            .as_str()
+            .ok_or_else(|| SettlementError::Malformed(format!("assinatura: {}", result)))?;
+
+        Ok(SettlementReceipt {
+            chain: self.chain().into(),
+            tx_ref: tx_ref.to_string(),
+            submitted_at: chrono::Utc::now().timestamp(),
+            valid_until,
+        })
+    }
+
+    async fn status(&self, receipt: &SettlementReceipt) -> Result<TxStatus, SettlementError> {
+        let result = json_rpc(
+            &self.client,
+            &self.endpoint,
+            "getSignatureStatuses",
+            json!([[receipt.tx_ref], { "searchTransactionHistory": true }]),
+        )
+        .await?;
+
+        let Some(st) = result.pointer("/value/0").filter(|v| !v.is_null()) else {
+            // desconhecida: só é terminal depois que o blockhash expirou
+            let Some(valid_until) = receipt.valid_until else {
+                return Ok(TxStatus::Pending);
+            };
+            let height = json_rpc(&self.client, &self.endpoint, "getBlockHeight", json!([{ "commitment": "finalized" }]))
+                .await?
+                .as_u64()
+                .ok_or_else(|| SettlementError::Malformed("getBlockHeight sem número".into()))?;
+            if height > valid_until {
+                return Ok(TxStatus::Failed {
+                    reason: format!("blockhash expirou (altura {} > {})", height, valid_until),
+                });
+            }
+            return Ok(TxStatus::Pending);
+        };
+
+        if let Some(err) = st.get("err").filter(|e| !e.is_null()) {
+            return Ok(TxStatus::Failed { reason: err.to_string() });
+        }
+
+        let height = st.get("slot").and_then(|s| s.as_u64());
+
+        match st.get("confirmationStatus").and_then(|c| c.as_str()) {
+            Some("finalized") => Ok(TxStatus::Finalized { height }),
+            _ => Ok(TxStatus::Included { height }),
+        }
+    }
+}
+
+// =========================================================
+// MOCK CHAIN (arquivo local, para testes offline)
+// =========================================================
+
+#[derive(Serialize, Deserialize)]
+struct MockBlock {
+    height: u64,
+    tx_ref: String,
+    commitment: Commitment,
+    timestamp: i64,
+}
+
+pub struct MockChainAdapter {
+    path: PathBuf,
+    operator: String,
+    /// segundos até uma tx incluída virar final
+    finality_secs: i64,
+}
+
+impl MockChainAdapter {
+    pub fn new(path: &str, operator: String, finality_secs: i64) -> Self {
+        Self { path: PathBuf::from(path), operator, finality_secs }
+    }
+
+    fn blocks(&self) -> Result<Vec<MockBlock>, SettlementError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut out = Vec::new();
+        for line in BufReader::new(File::open(&self.path)?).lines() {
+            if let Ok(b) = serde_json::from_str::<MockBlock>(line?.trim()) {
+                out.push(b);
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[async_trait]
+impl SettlementAdapter for MockChainAdapter {
+    fn chain(&self) -> &str {
+        "mock"
+    }
+
+    fn operator(&self) -> &str {
+        &self.operator
+    }
+
+    async fn submit(&self, commitment: &Commitment) -> Result<SettlementReceipt, SettlementError> {
+        if let Some(p) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(p)?;
+        }
+
+        let height = self.blocks()?.last().map_or(1, |b| b.height + 1);
+        let timestamp = chrono::Utc::now().timestamp();
+        let body = serde_json::to_vec(&(height, commitment, timestamp))
+            .map_err(|e| SettlementError::Malformed(e.to_string()))?;
+
+        let block = MockBlock {
+            height,
+            tx_ref: hex::encode(keccak256(&body)),
+            commitment: commitment.clone(),
+            timestamp,
+        };
+
+        let mut f = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(f, "{}", serde_json::to_string(&block).map_err(|e| SettlementError::Malformed(e.to_string()))?)?;
+        f.sync_data()?;
+
+        Ok(SettlementReceipt {
+            chain: self.chain().into(),
+            tx_ref: block.tx_ref,
+            submitted_at: timestamp,
+            valid_until: None,
+        })
+    }
+
+    async fn status(&self, receipt: &SettlementReceipt) -> Result<TxStatus, SettlementError> {
+        let now = chrono::Utc::now().timestamp();
+        Ok(match self.blocks()?.into_iter().find(|b| b.tx_ref == receipt.tx_ref) {
+            None => TxStatus::Pending,
+            Some(b) if now - b.timestamp >= self.finality_secs => TxStatus::Finalized { height: Some(b.height) },
+            Some(b) => TxStatus::Included { height: Some(b.height) },
+        })
+    }
+}
+
+// =========================================================
+// CONFIG
+// =========================================================
+
+/// Monta o adaptador a partir da seção [settlement] (config.rs):
+///   backend = ama | solana | mock
+///   ama_rpc, operator                      (ama)
+///   solana_rpc, solana_keypair             (solana)
+///   mock_chain_path, mock_finality_secs    (mock)
+pub fn adapter_from_config(cfg: &SettlementConfig, timeout: Duration) -> Result<Box<dyn SettlementAdapter>, String> {
+    match cfg.backend.as_str() {
+        "ama" => Ok(Box::new(AmaAdapter::new(
+            cfg.ama_rpc.clone(),
+            cfg.operator.clone().ok_or("settlement.operator é obrigatório para ama")?,
+            timeout,
+        ))),
+        "solana" => Ok(Box::new(SolanaMemoAdapter::from_keypair_file(
+            cfg.solana_rpc.clone(),
+            cfg.solana_keypair.as_deref().ok_or("settlement.solana_keypair é obrigatório para solana")?,
+            timeout,
+        )?)),
+        "mock" => Ok(Box::new(MockChainAdapter::new(
+            &cfg.mock_chain_path,
+            cfg.operator.clone().unwrap_or_else(|| "mock-operator".into()),
+            cfg.mock_finality_secs,
+        ))),
+        other => Err(format!("settlement desconhecido: {}", other)),
+    }
+}
@@ -0,0 +1,84 @@
+// =========================================================
+// 🔍 VERIFY INCLUSION — modo auditor (offline)
+// =========================================================
+//
+// uso: verify_inclusion <prova.json> --root <hex>
+//
+// <prova.json> é a resposta do GET /inclusion/{hash} do Semaphore.
+// --root é a raiz lida da chain e é obrigatória: o root_hash do próprio
+// JSON vem da mesma fonte que o caminho e não prova nada.
+
+use std::{env, fs, process};
+
+use terra_dourada_core::merkle::{verify_inclusion, PathStep};
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct InclusionJson {
+    proof_hash: String,
+    root_hash: String,
+    path: Vec<PathStep>,
+    batch_id: Option<u64>,
+    leaf_index: Option<u32>,
+    settlement: Option<Receipt>,
+}
+
+#[derive(Deserialize)]
+struct Receipt {
+    chain: String,
+    tx_ref: String,
+}
+
+fn parse_hash(label: &str, s: &str) -> [u8; 32] {
+    match hex::decode(s.trim()).ok().and_then(|b| <[u8; 32]>::try_from(b).ok()) {
+        Some(h) => h,
+        None => {
+            eprintln!("❌ {} não é hex de 32 bytes: {}", label, s);
+            process::exit(2);
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let root_arg = args.iter().position(|a| a == "--root").and_then(|i| args.get(i + 1));
+    let (Some(path), Some(root_hex)) = (args.get(1).filter(|a| *a != "--root"), root_arg) else {
+        eprintln!("uso: verify_inclusion <prova.json> --root <hex>");
+        eprintln!("   --root é a raiz ancorada, lida da chain (não a do JSON)");
+        process::exit(2);
+    };
+
+    let txt = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao ler {}: {}", path, e);
+        process::exit(2);
+    });
+
+    let prova: InclusionJson = serde_json::from_str(&txt).unwrap_or_else(|e| {
+        eprintln!("❌ JSON inválido: {}", e);
+        process::exit(2);
+    });
+
+    let proof_hash = parse_hash("proof_hash", &prova.proof_hash);
+    let root = parse_hash("root", root_hex);
+
+    println!("🔎 proof_hash = {}", prova.proof_hash);
+    if let (Some(b), Some(i)) = (prova.batch_id, prova.leaf_index) {
+        println!("📦 batch #{} · folha {}", b, i);
+    }
+    if let Some(r) = &prova.settlement {
+        println!("⛓️  {} tx = {}", r.chain, r.tx_ref);
+    }
+
+    if !prova.root_hash.trim().eq_ignore_ascii_case(&hex::encode(root)) {
+        println!("⚠️  root_hash do JSON ({}) difere da raiz informada", prova.root_hash.trim());
+    }
+
+    if verify_inclusion(&proof_hash, &prova.path, &root) {
+        println!("✅ Inclusão confirmada na raiz {}", hex::encode(root));
+    } else {
+        eprintln!("❌ O caminho não leva proof_hash até a raiz {}", hex::encode(root));
+        process::exit(1);
+    }
+}
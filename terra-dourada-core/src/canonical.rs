@@ -0,0 +1,232 @@
+// =========================================================
+// 📐 CANONICAL JSON — RFC 8785 (JCS)
+// =========================================================
+//
+// Mesma entrada lógica → mesmos bytes, em qualquer máquina:
+//   - objetos: chaves ordenadas por unidades UTF-16, sem espaços
+//   - strings: só escapa `"`, `\` e controles < 0x20 (\b \t \n \f \r,
+//     o resto como \u00xx minúsculo); todo o resto vai em UTF-8 puro
+//   - números: IEEE-754 double no formato do ECMAScript
+//     (Number.prototype.toString); -0 vira 0
+//
+// Inteiros fora de ±2^53 não cabem em double sem perda: são rejeitados
+// em vez de arredondados em silêncio (I-JSON, RFC 7493).
+//
+// Especificação publicada em "Canonical JSON.md"; vetores golden em
+// canonical_vectors.json (conferidos por canonical_check).
+
+use serde_json::{Map, Number, Value};
+use std::fmt;
+
+/// 2^53 — maior inteiro exato num double.
+const MAX_EXACT_INTEGER: u64 = 9_007_199_254_740_992;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanonicalError {
+    /// inteiro fora de ±2^53
+    UnsafeInteger(String),
+    /// NaN / ±Infinity
+    NonFinite,
+}
+
+impl fmt::Display for CanonicalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanonicalError::UnsafeInteger(n) => write!(f, "inteiro fora de ±2^53: {}", n),
+            CanonicalError::NonFinite => write!(f, "número não finito"),
+        }
+    }
+}
+
+impl std::error::Error for CanonicalError {}
+
+/// Forma canônica (JCS) de um valor JSON.
+pub fn canonicalize(value: &Value) -> Result<String, CanonicalError> {
+    let mut out = String::new();
+    write_value(&mut out, value)?;
+    Ok(out)
+}
+
+/// Atalho para qualquer `Serialize` (passa por `serde_json::Value`).
+pub fn to_canonical_string<T: serde::Serialize>(value: &T) -> Result<String, CanonicalError> {
+    // to_value só falha com chaves de mapa não-string, que nossos tipos não têm
+    let v = serde_json::to_value(value).expect("tipo serializável como JSON");
+    canonicalize(&v)
+}
+
+fn write_value(out: &mut String, value: &Value) -> Result<(), CanonicalError> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(out, n)?,
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => write_object(out, map)?,
+    }
+    Ok(())
+}
+
+fn write_object(out: &mut String, map: &Map<String, Value>) -> Result<(), CanonicalError> {
+    // ordem por unidades UTF-16 (≠ ordem de bytes UTF-8 fora do BMP)
+    let mut entries: Vec<(Vec<u16>, &String, &Value)> = map
+        .iter()
+        .map(|(k, v)| (k.encode_utf16().collect(), k, v))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    out.push('{');
+    for (i, (_, k, v)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_string(out, k);
+        out.push(':');
+        write_value(out, v)?;
+    }
+    out.push('}');
+    Ok(())
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_number(out: &mut String, n: &Number) -> Result<(), CanonicalError> {
+    if let Some(u) = n.as_u64() {
+        if u > MAX_EXACT_INTEGER {
+            return Err(CanonicalError::UnsafeInteger(n.to_string()));
+        }
+        out.push_str(&u.to_string());
+        return Ok(());
+    }
+    if let Some(i) = n.as_i64() {
+        if i.unsigned_abs() > MAX_EXACT_INTEGER {
+            return Err(CanonicalError::UnsafeInteger(n.to_string()));
+        }
+        out.push_str(&i.to_string());
+        return Ok(());
+    }
+    let f = n.as_f64().ok_or(CanonicalError::NonFinite)?;
+    out.push_str(&format_es_number(f)?);
+    Ok(())
+}
+
+/// Number.prototype.toString do ECMAScript (ECMA-262 §6.1.6.1.20).
+pub fn format_es_number(f: f64) -> Result<String, CanonicalError> {
+    if !f.is_finite() {
+        return Err(CanonicalError::NonFinite);
+    }
+    if f == 0.0 {
+        return Ok("0".into()); // inclui -0
+    }
+
+    let sign = if f < 0.0 { "-" } else { "" };
+
+    // `{:e}` do Rust já dá os dígitos mais curtos que voltam ao mesmo double
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exp) = sci.split_once('e').expect("formato {:e}");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exp: i32 = exp.parse().expect("expoente {:e}");
+
+    let k = digits.len() as i32; // nº de dígitos significativos
+    let n = exp + 1; // posição do ponto decimal
+
+    let body = if k <= n && n <= 21 {
+        // inteiro: dígitos + zeros
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        // ponto no meio
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        // 0.000ddd
+        format!("0.{}{}", "0".repeat((-n) as usize), digits)
+    } else {
+        // notação exponencial: d[.ddd]e±x
+        let e = n - 1;
+        let e_sign = if e < 0 { '-' } else { '+' };
+        if k == 1 {
+            format!("{}e{}{}", digits, e_sign, e.abs())
+        } else {
+            format!("{}.{}e{}{}", &digits[..1], &digits[1..], e_sign, e.abs())
+        }
+    };
+
+    Ok(format!("{}{}", sign, body))
+}
+
+// =========================================================
+// TESTES — vetores golden (os mesmos do canonical_check)
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    const VETORES: &str = include_str!("../../terra-dourada-tech/canonical_vectors.json");
+
+    fn vetores() -> Value {
+        serde_json::from_str(VETORES).expect("canonical_vectors.json inválido")
+    }
+
+    fn error_name(e: &CanonicalError) -> &'static str {
+        match e {
+            CanonicalError::UnsafeInteger(_) => "UnsafeInteger",
+            CanonicalError::NonFinite => "NonFinite",
+        }
+    }
+
+    #[test]
+    fn casos_jcs() {
+        let v = vetores();
+        for case in v["cases"].as_array().unwrap() {
+            let name = case["name"].as_str().unwrap();
+            let input: Value = serde_json::from_str(case["input"].as_str().unwrap()).unwrap();
+            match (canonicalize(&input), case["canonical"].as_str(), case["error"].as_str()) {
+                (Ok(got), Some(want), _) => assert_eq!(got, want, "{}", name),
+                (Err(e), None, Some(want)) => assert_eq!(error_name(&e), want, "{}", name),
+                (got, _, _) => panic!("{}: obtido {:?}", name, got),
+            }
+        }
+    }
+
+    #[test]
+    fn payload_mel() {
+        let v = vetores();
+        for case in v["mel"].as_array().unwrap() {
+            let name = case["name"].as_str().unwrap();
+            let claim: Value = serde_json::from_str(case["claim"].as_str().unwrap()).unwrap();
+            let payload = format!("{}:{}", canonicalize(&claim).unwrap(), case["timestamp"].as_u64().unwrap());
+            assert_eq!(payload, case["payload"].as_str().unwrap(), "{}", name);
+            assert_eq!(
+                hex::encode(Sha256::digest(payload.as_bytes())),
+                case["payload_sha256"].as_str().unwrap(),
+                "{}",
+                name
+            );
+        }
+    }
+}
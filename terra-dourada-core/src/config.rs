@@ -0,0 +1,783 @@
+// =========================================================
+// ⚙️ CONFIG — arquivo TOML + ambiente + linha de comando
+// =========================================================
+//
+// Comum a server (8080), semaphore (3030), info_local (7070) e
+// info_global (9090). Precedência, do mais fraco para o mais forte:
+//
+//   padrões  <  arquivo TOML  <  variáveis TD_*  <  flags
+//
+// Arquivo: --config <arquivo> | TD_CONFIG | ./terra-dourada.toml (se existir)
+//
+// Flags:   --listen <ip:porta>             endereço deste serviço
+//          --tls-cert <pem> --tls-key <pem> TLS deste serviço
+//          --set <seção.chave=valor>       qualquer chave (repetível)
+//          --print-config                  imprime os valores efetivos e sai
+//
+// Tudo é validado antes de abrir porta. Segredos (PINATA_JWT,
+// TD_ARWEAVE_TOKEN, TD_KEYSTORE_PASSPHRASE, TD_ADMIN_TOKEN) ficam só no
+// ambiente: não entram no arquivo nem no --print-config.
+
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+use crate::keystore::{DEFAULT_OVERLAP_SECS, DEFAULT_ROTATION_SECS};
+use crate::ledger::FsyncPolicy;
+use crate::zk_verify::{DEFAULT_K, MAX_K};
+
+const DEFAULT_FILE: &str = "terra-dourada.toml";
+
+pub const STORE_BACKENDS: &[&str] = &["pinata", "local", "arweave"];
+pub const SETTLEMENT_BACKENDS: &[&str] = &["ama", "solana", "mock"];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    Server,
+    Semaphore,
+    InfoLocal,
+    InfoGlobal,
+}
+
+impl Service {
+    pub fn section(self) -> &'static str {
+        match self {
+            Service::Server => "server",
+            Service::Semaphore => "semaphore",
+            Service::InfoLocal => "info_local",
+            Service::InfoGlobal => "info_global",
+        }
+    }
+}
+
+// =========================================================
+// SEÇÕES
+// =========================================================
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// certificado PEM (cadeia completa)
+    pub cert: String,
+    /// chave privada PEM
+    pub key: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ListenConfig {
+    pub listen: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+}
+
+impl ListenConfig {
+    fn on(port: u16) -> Self {
+        Self { listen: format!("127.0.0.1:{}", port), tls: None }
+    }
+
+    /// Já validado no `load`.
+    pub fn addr(&self) -> SocketAddr {
+        self.listen.parse().expect("listen validado no load")
+    }
+
+    pub fn url(&self) -> String {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        format!("{}://{}", scheme, self.listen)
+    }
+}
+
+/// info_local / info_global: endereço + ledger e mente de cada um.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct InfoConfig {
+    pub listen: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    pub ledger_path: String,
+    pub treino_input: String,
+    pub mind_path: String,
+    pub result_path: String,
+    /// "always" | "never" | N (fsync a cada N entradas), ver ledger.rs
+    pub ledger_fsync: String,
+}
+
+impl InfoConfig {
+    fn on(port: u16, prefix: &str) -> Self {
+        Self {
+            listen: format!("127.0.0.1:{}", port),
+            tls: None,
+            ledger_path: format!("src/data/{}ledger.log", prefix),
+            treino_input: format!("src/data/{}treino_input.txt", prefix),
+            mind_path: format!("src/data/{}mind.bin", prefix),
+            result_path: format!("src/data/{}resultados_fxl.txt", prefix),
+            ledger_fsync: "always".into(),
+        }
+    }
+
+    pub fn net(&self) -> ListenConfig {
+        ListenConfig { listen: self.listen.clone(), tls: self.tls.clone() }
+    }
+
+    /// Já validado no `load`.
+    pub fn fsync(&self) -> FsyncPolicy {
+        FsyncPolicy::parse(&self.ledger_fsync).expect("ledger_fsync validado no load")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UpstreamConfig {
+    /// POST do challenge-response (prover_client.rs)
+    pub prover_url: String,
+    /// base do info_local (o server chama {url}/append)
+    pub info_local_url: String,
+    /// base do info_global (o server chama {url}/commit); ausente → não notifica
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_global_url: Option<String>,
+    /// novas tentativas do challenge-response além da primeira
+    pub prover_retries: u32,
+    /// idade máxima de uma resposta assinada do prover
+    pub prover_max_age_secs: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TimeoutConfig {
+    pub prover_ms: u64,
+    pub info_local_ms: u64,
+    pub info_global_ms: u64,
+    /// artifact stores (Pinata / Arweave)
+    pub store_ms: u64,
+    /// RPC de settlement (AMA / Solana)
+    pub settlement_ms: u64,
+}
+
+impl TimeoutConfig {
+    pub fn prover(&self) -> Duration {
+        Duration::from_millis(self.prover_ms)
+    }
+
+    pub fn info_local(&self) -> Duration {
+        Duration::from_millis(self.info_local_ms)
+    }
+
+    pub fn info_global(&self) -> Duration {
+        Duration::from_millis(self.info_global_ms)
+    }
+
+    pub fn store(&self) -> Duration {
+        Duration::from_millis(self.store_ms)
+    }
+
+    pub fn settlement(&self) -> Duration {
+        Duration::from_millis(self.settlement_ms)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct StorageConfig {
+    /// backend dos reports (POST /mel)
+    pub reports: String,
+    /// backend dos artefatos de batch (Semaphore)
+    pub batches: String,
+    /// backend das mídias; ausente → o mesmo dos reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<String>,
+    pub pinata_api_url: String,
+    pub pinata_gateway: String,
+    pub local_cas_dir: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arweave_upload_url: Option<String>,
+    pub arweave_gateway: String,
+}
+
+impl StorageConfig {
+    pub fn media_backend(&self) -> &str {
+        self.media.as_deref().unwrap_or(&self.reports)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MediaConfig {
+    /// uploads multipart em andamento (POST /mel/media)
+    pub tmp_dir: String,
+    pub max_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KeysConfig {
+    /// validade de cada chave Dilithium2 dos tenants
+    pub rotation_secs: u64,
+    /// quanto antes do fim a sucessora é publicada
+    pub overlap_secs: u64,
+    /// assinatura destacada no /mel e no artefato
+    pub publish_signature: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ZkConfig {
+    /// k esperado do circuito td.fp.v1
+    pub k: u32,
+    /// aprovadores M-de-N das VKs
+    pub vk_approvers: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TenantsConfig {
+    pub dir: String,
+    /// rate limit de tenants criados sem um próprio
+    pub rate_per_min: u32,
+    /// arquivos do modo single-tenant, migrados para o tenant legado
+    pub legacy_proof_store: String,
+    pub legacy_keystore_dir: String,
+    pub legacy_quarantine: String,
+    pub legacy_vk_registry: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BatchConfig {
+    pub dir: String,
+    /// provas por batch
+    pub size: usize,
+    /// fecha o batch incompleto depois deste tempo
+    pub timeout_secs: u64,
+    pub replay_dir: String,
+    /// ausente → {dir}/outbox.jsonl
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outbox_path: Option<String>,
+    /// tentativas de ancoragem antes de `failed`
+    pub anchor_max_attempts: u32,
+}
+
+impl BatchConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    pub fn outbox(&self) -> String {
+        self.outbox_path.clone().unwrap_or_else(|| format!("{}/outbox.jsonl", self.dir))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SettlementConfig {
+    pub backend: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    pub ama_rpc: String,
+    pub solana_rpc: String,
+    /// keypair do solana-keygen (array JSON de 64 bytes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solana_keypair: Option<String>,
+    pub mock_chain_path: String,
+    pub mock_finality_secs: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub server: ListenConfig,
+    pub semaphore: ListenConfig,
+    pub info_local: InfoConfig,
+    pub info_global: InfoConfig,
+    pub upstream: UpstreamConfig,
+    pub timeouts: TimeoutConfig,
+    pub storage: StorageConfig,
+    pub media: MediaConfig,
+    pub keys: KeysConfig,
+    pub zk: ZkConfig,
+    pub tenants: TenantsConfig,
+    pub batch: BatchConfig,
+    pub settlement: SettlementConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            server: ListenConfig::on(8080),
+            semaphore: ListenConfig::on(3030),
+            // o ledger do local tem nome próprio; o global usa o prefixo global_
+            info_local: InfoConfig { ledger_path: "src/data/info_ledger.log".into(), ..InfoConfig::on(7070, "") },
+            info_global: InfoConfig::on(9090, "global_"),
+            upstream: UpstreamConfig {
+                prover_url: "http://127.0.0.1:8081/prove".into(),
+                info_local_url: "http://127.0.0.1:7070".into(),
+                info_global_url: None,
+                prover_retries: 2,
+                prover_max_age_secs: 120,
+            },
+            timeouts: TimeoutConfig {
+                prover_ms: 30_000,
+                info_local_ms: 2_000,
+                info_global_ms: 5_000,
+                store_ms: 30_000,
+                settlement_ms: 15_000,
+            },
+            storage: StorageConfig {
+                reports: "pinata".into(),
+                batches: "pinata".into(),
+                media: None,
+                pinata_api_url: "https://api.pinata.cloud".into(),
+                pinata_gateway: "https://gateway.pinata.cloud/ipfs".into(),
+                local_cas_dir: "src/data/cas".into(),
+                arweave_upload_url: None,
+                arweave_gateway: "https://arweave.net".into(),
+            },
+            media: MediaConfig {
+                tmp_dir: "src/data/tmp".into(),
+                max_bytes: 512 * 1024 * 1024,
+            },
+            keys: KeysConfig {
+                rotation_secs: DEFAULT_ROTATION_SECS,
+                overlap_secs: DEFAULT_OVERLAP_SECS,
+                publish_signature: false,
+            },
+            zk: ZkConfig {
+                k: DEFAULT_K,
+                vk_approvers: "src/data/vk_approvers.json".into(),
+            },
+            tenants: TenantsConfig {
+                dir: "src/data/tenants".into(),
+                rate_per_min: 60,
+                legacy_proof_store: "src/data/mel_proofs.jsonl".into(),
+                legacy_keystore_dir: "src/data/keys".into(),
+                legacy_quarantine: "src/data/quarantine.jsonl".into(),
+                legacy_vk_registry: "src/data/vk_registry.json".into(),
+            },
+            batch: BatchConfig {
+                dir: "src/data/batches".into(),
+                size: 64,
+                timeout_secs: 30,
+                replay_dir: "src/data/replay".into(),
+                outbox_path: None,
+                anchor_max_attempts: 8,
+            },
+            settlement: SettlementConfig {
+                backend: "ama".into(),
+                operator: None,
+                ama_rpc: "https://rpc.amadeus.network:26657".into(),
+                solana_rpc: "https://api.mainnet-beta.solana.com".into(),
+                solana_keypair: None,
+                mock_chain_path: "src/data/mock_chain.jsonl".into(),
+                mock_finality_secs: 0,
+            },
+        }
+    }
+}
+
+// =========================================================
+// AMBIENTE (nomes antigos continuam valendo)
+// =========================================================
+
+const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("TD_SERVER_LISTEN", "server.listen"),
+    ("TD_SERVER_TLS_CERT", "server.tls.cert"),
+    ("TD_SERVER_TLS_KEY", "server.tls.key"),
+    ("TD_SEMAPHORE_LISTEN", "semaphore.listen"),
+    ("TD_SEMAPHORE_TLS_CERT", "semaphore.tls.cert"),
+    ("TD_SEMAPHORE_TLS_KEY", "semaphore.tls.key"),
+    ("TD_INFO_LOCAL_LISTEN", "info_local.listen"),
+    ("TD_INFO_LOCAL_TLS_CERT", "info_local.tls.cert"),
+    ("TD_INFO_LOCAL_TLS_KEY", "info_local.tls.key"),
+    ("TD_LEDGER_PATH", "info_local.ledger_path"),
+    ("TD_TREINO_INPUT", "info_local.treino_input"),
+    ("TD_MIND_PATH", "info_local.mind_path"),
+    ("TD_RESULT_PATH", "info_local.result_path"),
+    // vale para os dois serviços; TD_GLOBAL_LEDGER_FSYNC sobrepõe no global
+    ("TD_LEDGER_FSYNC", "info_local.ledger_fsync"),
+    ("TD_LEDGER_FSYNC", "info_global.ledger_fsync"),
+    ("TD_INFO_GLOBAL_LISTEN", "info_global.listen"),
+    ("TD_INFO_GLOBAL_TLS_CERT", "info_global.tls.cert"),
+    ("TD_INFO_GLOBAL_TLS_KEY", "info_global.tls.key"),
+    ("TD_GLOBAL_LEDGER_PATH", "info_global.ledger_path"),
+    ("TD_GLOBAL_TREINO_INPUT", "info_global.treino_input"),
+    ("TD_GLOBAL_MIND_PATH", "info_global.mind_path"),
+    ("TD_GLOBAL_RESULT_PATH", "info_global.result_path"),
+    ("TD_GLOBAL_LEDGER_FSYNC", "info_global.ledger_fsync"),
+    ("TD_PROVER_URL", "upstream.prover_url"),
+    ("TD_INFO_LOCAL_URL", "upstream.info_local_url"),
+    ("TD_INFO_GLOBAL_URL", "upstream.info_global_url"),
+    ("TD_PROVER_RETRIES", "upstream.prover_retries"),
+    ("TD_PROVER_MAX_AGE_SECS", "upstream.prover_max_age_secs"),
+    ("TD_PROVER_TIMEOUT_MS", "timeouts.prover_ms"),
+    ("TD_INFO_LOCAL_TIMEOUT_MS", "timeouts.info_local_ms"),
+    ("TD_INFO_GLOBAL_TIMEOUT_MS", "timeouts.info_global_ms"),
+    ("TD_STORE_TIMEOUT_MS", "timeouts.store_ms"),
+    ("TD_SETTLEMENT_TIMEOUT_MS", "timeouts.settlement_ms"),
+    ("TD_STORE_REPORTS", "storage.reports"),
+    ("TD_STORE_BATCHES", "storage.batches"),
+    ("TD_STORE_MEDIA", "storage.media"),
+    ("TD_PINATA_API_URL", "storage.pinata_api_url"),
+    ("TD_PINATA_GATEWAY", "storage.pinata_gateway"),
+    ("TD_LOCAL_CAS_DIR", "storage.local_cas_dir"),
+    ("TD_ARWEAVE_UPLOAD_URL", "storage.arweave_upload_url"),
+    ("TD_ARWEAVE_GATEWAY", "storage.arweave_gateway"),
+    ("TD_MEDIA_TMP_DIR", "media.tmp_dir"),
+    ("TD_MEDIA_MAX_BYTES", "media.max_bytes"),
+    ("TD_KEY_ROTATION_SECS", "keys.rotation_secs"),
+    ("TD_KEY_OVERLAP_SECS", "keys.overlap_secs"),
+    ("TD_PUBLISH_SIGNATURE", "keys.publish_signature"),
+    ("TD_ZK_K", "zk.k"),
+    ("TD_VK_APPROVERS", "zk.vk_approvers"),
+    ("TD_TENANTS_DIR", "tenants.dir"),
+    ("TD_TENANT_RATE_PER_MIN", "tenants.rate_per_min"),
+    ("TD_PROOF_STORE_PATH", "tenants.legacy_proof_store"),
+    ("TD_KEYSTORE_DIR", "tenants.legacy_keystore_dir"),
+    ("TD_QUARANTINE_PATH", "tenants.legacy_quarantine"),
+    ("TD_VK_REGISTRY_PATH", "tenants.legacy_vk_registry"),
+    ("TD_BATCH_DIR", "batch.dir"),
+    ("TD_BATCH_SIZE", "batch.size"),
+    ("TD_BATCH_TIMEOUT_SECS", "batch.timeout_secs"),
+    ("TD_REPLAY_DIR", "batch.replay_dir"),
+    ("TD_OUTBOX_PATH", "batch.outbox_path"),
+    ("TD_ANCHOR_MAX_ATTEMPTS", "batch.anchor_max_attempts"),
+    ("TD_SETTLEMENT", "settlement.backend"),
+    ("TD_OPERATOR", "settlement.operator"),
+    ("TD_AMA_RPC", "settlement.ama_rpc"),
+    ("TD_SOLANA_RPC", "settlement.solana_rpc"),
+    ("TD_SOLANA_KEYPAIR", "settlement.solana_keypair"),
+    ("TD_MOCK_CHAIN_PATH", "settlement.mock_chain_path"),
+    ("TD_MOCK_FINALITY_SECS", "settlement.mock_finality_secs"),
+];
+
+// =========================================================
+// MERGE
+// =========================================================
+
+fn merge(base: &mut toml::Value, over: toml::Value) {
+    match (base, over) {
+        (toml::Value::Table(b), toml::Value::Table(o)) => {
+            for (k, v) in o {
+                match b.get_mut(&k) {
+                    Some(slot) => merge(slot, v),
+                    None => {
+                        b.insert(k, v);
+                    }
+                }
+            }
+        }
+        (slot, v) => *slot = v,
+    }
+}
+
+/// Aplica `seção.chave = valor` vindo de texto (env ou CLI). O tipo é o
+/// do valor padrão naquela chave; chaves opcionais sem padrão são texto.
+fn set_path(root: &mut toml::Value, path: &str, raw: &str, origin: &str) -> Result<(), String> {
+    let keys: Vec<&str> = path.split('.').collect();
+    let (last, parents) = keys.split_last().expect("split nunca é vazio");
+
+    let mut cur = root;
+    for k in parents {
+        let table = cur
+            .as_table_mut()
+            .ok_or_else(|| format!("{}: {} não é uma seção", origin, path))?;
+        cur = table
+            .entry(k.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    }
+    let table = cur
+        .as_table_mut()
+        .ok_or_else(|| format!("{}: {} não é uma seção", origin, path))?;
+
+    let value = match table.get(*last) {
+        Some(toml::Value::Integer(_)) => toml::Value::Integer(
+            raw.trim()
+                .parse()
+                .map_err(|_| format!("{}: {} espera um inteiro, veio {:?}", origin, path, raw))?,
+        ),
+        Some(toml::Value::Boolean(_)) => toml::Value::Boolean(match raw.trim() {
+            "true" | "1" => true,
+            "false" | "0" => false,
+            _ => return Err(format!("{}: {} espera true/false, veio {:?}", origin, path, raw)),
+        }),
+        Some(toml::Value::Table(_)) => return Err(format!("{}: {} é uma seção", origin, path)),
+        _ => toml::Value::String(raw.trim().into()),
+    };
+    table.insert(last.to_string(), value);
+    Ok(())
+}
+
+// =========================================================
+// CLI
+// =========================================================
+
+struct Cli {
+    config: Option<String>,
+    print: bool,
+    sets: Vec<(String, String)>,
+}
+
+fn usage(service: Service) -> String {
+    format!(
+        "uso: {} [--config <arquivo.toml>] [--listen <ip:porta>] [--tls-cert <pem> --tls-key <pem>]\n\
+         {}    [--set <seção.chave=valor>]... [--print-config]",
+        service.section(),
+        " ".repeat(service.section().len())
+    )
+}
+
+fn parse_args(service: Service, args: impl IntoIterator<Item = String>) -> Result<Cli, String> {
+    let mut cli = Cli { config: None, print: false, sets: Vec::new() };
+    let section = service.section();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("{} exige um valor", flag));
+        match arg.as_str() {
+            "--config" => cli.config = Some(value("--config")?),
+            "--print-config" => cli.print = true,
+            "--listen" => cli.sets.push((format!("{}.listen", section), value("--listen")?)),
+            "--tls-cert" => cli.sets.push((format!("{}.tls.cert", section), value("--tls-cert")?)),
+            "--tls-key" => cli.sets.push((format!("{}.tls.key", section), value("--tls-key")?)),
+            "--set" => {
+                let kv = value("--set")?;
+                let (k, v) = kv
+                    .split_once('=')
+                    .ok_or_else(|| format!("--set espera seção.chave=valor, veio {:?}", kv))?;
+                cli.sets.push((k.trim().into(), v.into()));
+            }
+            "-h" | "--help" => {
+                println!("{}", usage(service));
+                process::exit(0);
+            }
+            other => return Err(format!("argumento desconhecido: {}\n{}", other, usage(service))),
+        }
+    }
+    Ok(cli)
+}
+
+// =========================================================
+// VALIDAÇÃO
+// =========================================================
+
+fn check_url(errs: &mut Vec<String>, key: &str, url: &str) {
+    let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"));
+    if rest.is_none_or(|r| r.is_empty() || r.starts_with('/')) {
+        errs.push(format!("{}: URL inválida {:?} (http:// ou https://)", key, url));
+    }
+}
+
+fn check_positive(errs: &mut Vec<String>, key: &str, v: u64) {
+    if v == 0 {
+        errs.push(format!("{}: tem que ser > 0", key));
+    }
+}
+
+fn check_listen(errs: &mut Vec<String>, section: &str, l: &ListenConfig, files: bool) {
+    if l.listen.parse::<SocketAddr>().is_err() {
+        errs.push(format!("{}.listen: endereço inválido {:?} (ip:porta)", section, l.listen));
+    }
+    if let (Some(tls), true) = (&l.tls, files) {
+        for (k, p) in [("cert", &tls.cert), ("key", &tls.key)] {
+            if !Path::new(p).is_file() {
+                errs.push(format!("{}.tls.{}: arquivo {:?} não encontrado", section, k, p));
+            }
+        }
+    }
+}
+
+impl Config {
+    pub fn listen(&self, service: Service) -> ListenConfig {
+        match service {
+            Service::Server => self.server.clone(),
+            Service::Semaphore => self.semaphore.clone(),
+            Service::InfoLocal => self.info_local.net(),
+            Service::InfoGlobal => self.info_global.net(),
+        }
+    }
+
+    /// Valida o que `service` usa; as outras seções só precisam ser bem formadas.
+    pub fn validate(&self, service: Service) -> Result<(), String> {
+        let mut errs = Vec::new();
+
+        for svc in [Service::Server, Service::Semaphore, Service::InfoLocal, Service::InfoGlobal] {
+            check_listen(&mut errs, svc.section(), &self.listen(svc), svc == service);
+        }
+
+        for (svc, info) in [(Service::InfoLocal, &self.info_local), (Service::InfoGlobal, &self.info_global)] {
+            if let Err(e) = FsyncPolicy::parse(&info.ledger_fsync) {
+                errs.push(format!("{}.ledger_fsync: {}", svc.section(), e));
+            }
+        }
+
+        let t = &self.timeouts;
+        for (k, v) in [
+            ("prover_ms", t.prover_ms),
+            ("info_local_ms", t.info_local_ms),
+            ("info_global_ms", t.info_global_ms),
+            ("store_ms", t.store_ms),
+            ("settlement_ms", t.settlement_ms),
+        ] {
+            if v == 0 {
+                errs.push(format!("timeouts.{}: tem que ser > 0", k));
+            }
+        }
+
+        if service == Service::Server {
+            let u = &self.upstream;
+            check_url(&mut errs, "upstream.prover_url", &u.prover_url);
+            check_url(&mut errs, "upstream.info_local_url", &u.info_local_url);
+            if let Some(url) = &u.info_global_url {
+                check_url(&mut errs, "upstream.info_global_url", url);
+            }
+            check_positive(&mut errs, "upstream.prover_max_age_secs", u.prover_max_age_secs);
+
+            check_positive(&mut errs, "media.max_bytes", self.media.max_bytes);
+
+            let k = &self.keys;
+            check_positive(&mut errs, "keys.rotation_secs", k.rotation_secs);
+            if k.overlap_secs >= k.rotation_secs {
+                errs.push(format!(
+                    "keys.overlap_secs: {} tem que ser menor que keys.rotation_secs ({})",
+                    k.overlap_secs, k.rotation_secs
+                ));
+            }
+
+            if self.zk.k == 0 || self.zk.k > MAX_K {
+                errs.push(format!("zk.k: {} fora de 1..={}", self.zk.k, MAX_K));
+            }
+
+            check_positive(&mut errs, "tenants.rate_per_min", self.tenants.rate_per_min as u64);
+        }
+
+        if matches!(service, Service::Server | Service::Semaphore) {
+            let s = &self.storage;
+            let mut used = vec![("reports", s.reports.as_str()), ("batches", s.batches.as_str())];
+            if let Some(media) = &s.media {
+                used.push(("media", media.as_str()));
+            }
+            for &(k, backend) in &used {
+                if !STORE_BACKENDS.contains(&backend) {
+                    errs.push(format!("storage.{}: backend {:?} desconhecido ({})", k, backend, STORE_BACKENDS.join(" | ")));
+                }
+            }
+            check_url(&mut errs, "storage.pinata_api_url", &s.pinata_api_url);
+            check_url(&mut errs, "storage.pinata_gateway", &s.pinata_gateway);
+            check_url(&mut errs, "storage.arweave_gateway", &s.arweave_gateway);
+            match &s.arweave_upload_url {
+                Some(url) => check_url(&mut errs, "storage.arweave_upload_url", url),
+                None if used.iter().any(|(_, b)| *b == "arweave") => {
+                    errs.push("storage.arweave_upload_url: obrigatório para o store arweave".into())
+                }
+                None => {}
+            }
+        }
+
+        if service == Service::Semaphore {
+            let b = &self.batch;
+            check_positive(&mut errs, "batch.size", b.size as u64);
+            check_positive(&mut errs, "batch.timeout_secs", b.timeout_secs);
+            check_positive(&mut errs, "batch.anchor_max_attempts", b.anchor_max_attempts as u64);
+
+            let st = &self.settlement;
+            match st.backend.as_str() {
+                "ama" => {
+                    check_url(&mut errs, "settlement.ama_rpc", &st.ama_rpc);
+                    if st.operator.is_none() {
+                        errs.push("settlement.operator: obrigatório para settlement ama".into());
+                    }
+                }
+                "solana" => {
+                    check_url(&mut errs, "settlement.solana_rpc", &st.solana_rpc);
+                    if st.solana_keypair.is_none() {
+                        errs.push("settlement.solana_keypair: obrigatório para settlement solana".into());
+                    }
+                }
+                "mock" => {
+                    if st.mock_finality_secs < 0 {
+                        errs.push("settlement.mock_finality_secs: não pode ser negativo".into());
+                    }
+                }
+                other => errs.push(format!(
+                    "settlement.backend: {:?} desconhecido ({})",
+                    other,
+                    SETTLEMENT_BACKENDS.join(" | ")
+                )),
+            }
+        }
+
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            Err(errs.join("\n   "))
+        }
+    }
+}
+
+// =========================================================
+// LOAD
+// =========================================================
+
+/// Padrões → arquivo → ambiente → flags, validado para `service`.
+/// Devolve também a origem (arquivo lido ou "padrões").
+fn build(service: Service, cli: &Cli) -> Result<(Config, String), String> {
+    let mut tree = toml::Value::try_from(Config::default()).map_err(|e| format!("padrões: {}", e))?;
+
+    let env_file = std::env::var("TD_CONFIG").ok().filter(|v| !v.trim().is_empty());
+    let file = match (&cli.config, env_file) {
+        (Some(p), _) => Some(p.clone()),
+        (None, Some(p)) => Some(p),
+        (None, None) => Path::new(DEFAULT_FILE).exists().then(|| DEFAULT_FILE.to_string()),
+    };
+
+    let source = match &file {
+        Some(path) => {
+            let txt = std::fs::read_to_string(path).map_err(|e| format!("falha ao ler {}: {}", path, e))?;
+            let parsed: toml::Value =
+                toml::from_str(&txt).map_err(|e| format!("{}: {}", path, e.to_string().trim_end().replace('\n', " ")))?;
+            merge(&mut tree, parsed);
+            path.clone()
+        }
+        None => "padrões".to_string(),
+    };
+
+    for (var, path) in ENV_OVERRIDES {
+        if let Some(v) = std::env::var(var).ok().filter(|v| !v.trim().is_empty()) {
+            set_path(&mut tree, path, &v, var)?;
+        }
+    }
+    for (path, v) in &cli.sets {
+        set_path(&mut tree, path, v, "linha de comando")?;
+    }
+
+    let cfg: Config = tree
+        .try_into()
+        .map_err(|e: toml::de::Error| format!("{} + ambiente + flags: {}", source, e.to_string().trim_end().replace('\n', " ")))?;
+    cfg.validate(service)?;
+    Ok((cfg, source))
+}
+
+/// Carrega a configuração do serviço; erro → mensagem e exit 2.
+/// Com --print-config imprime os valores efetivos (TOML) e sai.
+pub fn load(service: Service) -> Config {
+    let result = parse_args(service, std::env::args().skip(1)).and_then(|cli| {
+        let (cfg, source) = build(service, &cli)?;
+        Ok((cfg, source, cli.print))
+    });
+
+    match result {
+        Ok((cfg, source, true)) => {
+            println!("# configuração efetiva de {} (origem: {} + ambiente + flags)", service.section(), source);
+            print!("{}", toml::to_string_pretty(&cfg).expect("Config serializável"));
+            process::exit(0);
+        }
+        Ok((cfg, source, false)) => {
+            println!("⚙️ Configuração: {} + ambiente + flags", source);
+            cfg
+        }
+        Err(e) => {
+            eprintln!("❌ Configuração inválida:\n   {}", e);
+            process::exit(2);
+        }
+    }
+}
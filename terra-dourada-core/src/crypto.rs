@@ -0,0 +1,64 @@
+// =========================================================
+// 🔥 CRYPTO — DK-PQC-HMAC, PQC-hash → Fp e Keccak-256
+// =========================================================
+//
+// dk_pqc_hmac:
+//   h1  = HMAC-SHA256(key, payload)
+//   h2  = SHA-256(Dilithium2.sign(payload))
+//   tag = HMAC-SHA256(key, h1 XOR h2)
+//
+// pqc_sign_hash_to_fp:
+//   h2 = SHA-256(Dilithium2.sign(SHA-256(payload)))
+//   Fp = from_raw(h2 como 4 limbs u64 little-endian)
+//
+// Detalhes e motivação em "🌟 DK-PQC-HMAC.md" e "PQC-Rooted ZK proof.md".
+
+use crystals_dilithium::dilithium2::Keypair;
+use hmac::{Hmac, Mac};
+use pasta_curves::pallas::Base as Fp;
+use sha2::{Digest, Sha256};
+use tiny_keccak::{Hasher, Keccak};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub fn dk_pqc_hmac(key: &[u8], payload: &[u8], keypair: &Keypair) -> [u8; 32] {
+    let mut mac1 = HmacSha256::new_from_slice(key).unwrap();
+    mac1.update(payload);
+    let h1 = mac1.finalize().into_bytes();
+
+    let signature = keypair.secret.sign(payload);
+    let h2 = Sha256::digest(signature);
+
+    let mut mix = [0u8; 32];
+    for (m, (a, b)) in mix.iter_mut().zip(h1.iter().zip(h2.iter())) {
+        *m = a ^ b;
+    }
+
+    let mut mac_final = HmacSha256::new_from_slice(key).unwrap();
+    mac_final.update(&mix);
+
+    mac_final.finalize().into_bytes().into()
+}
+
+pub fn pqc_sign_hash_to_fp(payload: &[u8], keypair: &Keypair) -> Fp {
+    let h1 = Sha256::digest(payload);
+    let signature = keypair.secret.sign(&h1);
+    let h2 = Sha256::digest(signature);
+
+    let limbs = [
+        u64::from_le_bytes(h2[0..8].try_into().unwrap()),
+        u64::from_le_bytes(h2[8..16].try_into().unwrap()),
+        u64::from_le_bytes(h2[16..24].try_into().unwrap()),
+        u64::from_le_bytes(h2[24..32].try_into().unwrap()),
+    ];
+
+    Fp::from_raw(limbs)
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut out = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut out);
+    out
+}
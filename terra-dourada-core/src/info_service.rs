@@ -0,0 +1,431 @@
+// =========================================================
+// 🧠 INFO SERVICE — ledger + mente do info_local e do info_global
+// =========================================================
+//
+// O que os dois serviços têm em comum: o writer único do ledger
+// (ledger.rs) com a mente FXL atualizada no mesmo thread, logo depois de
+// cada entrada, o GET /state e o GET /similar. Cada binário só monta os
+// campos da sua entrada (/append no local, /commit no global).
+//
+// O writer publica (versão, cabeça, fp_hex) depois de atualizar a mente:
+// o /state devolve esse instantâneo, sem reler nem re-hashear o mind.bin.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use warp::http::StatusCode;
+
+use crate::config::InfoConfig;
+use crate::fxl_turbo::treino::{IndiceSimilaridade, Similar, TreinoConfig, TreinoIncremental};
+use crate::ledger::{ensure_parent, fp_from_mind_and_version, read_file, LedgerHead, LedgerQueue, LedgerWriter};
+
+// =========================================================
+// ESTADO
+// =========================================================
+
+/// k padrão e máximo do GET /similar
+const SIMILAR_K: usize = 5;
+const SIMILAR_K_MAX: usize = 100;
+
+#[derive(Clone)]
+struct Paths {
+    ledger: String,
+    treino: String,
+    mind: String,
+    resultados: String,
+}
+
+/// Registros da mente para o GET /similar; o writer acrescenta a cada entrada.
+struct Memoria {
+    version: u64,
+    indice: IndiceSimilaridade,
+}
+
+/// Último estado publicado pelo writer (mente já atualizada).
+struct Publicado {
+    version: u64,
+    ledger_head: String,
+    fp_hex: String,
+}
+
+/// O que o writer devolve a cada entrada gravada.
+struct Mente {
+    fp_hex: String,
+    /// a mente não acompanhou a entrada (fp_hex é o do mind.bin em disco)
+    warning: Option<String>,
+}
+
+pub struct InfoService {
+    memoria: Arc<RwLock<Memoria>>,
+    publicado: Arc<RwLock<Publicado>>,
+    /// writer único do ledger
+    ledger: LedgerQueue<Mente>,
+    /// a última atualização da mente falhou depois da entrada gravada
+    degradada: Arc<AtomicBool>,
+    mind: String,
+}
+
+#[derive(Serialize)]
+pub struct StateResponse {
+    version: u64,
+    fp_hex: String,
+    /// `entry` da última linha do ledger (cabeça da cadeia)
+    ledger_head: String,
+    /// entrada gravada, mas mind.bin ainda não acompanha o ledger
+    /// (fp_hex é o do arquivo em disco); a próxima entrada refaz a mente
+    degraded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SimilarQuery {
+    q: String,
+    k: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct SimilarHit {
+    /// posição na mente (0 = primeira entrada do ledger)
+    index: usize,
+    text: String,
+    timestamp_ns: u64,
+    similarity_sha256: f64,
+    similarity_bytes: f64,
+    similarity_combined: f64,
+}
+
+#[derive(Serialize)]
+struct SimilarResponse {
+    version: u64,
+    query: String,
+    results: Vec<SimilarHit>,
+}
+
+// =========================================================
+// MENTE
+// =========================================================
+
+fn treino_config(paths: &Paths) -> TreinoConfig {
+    // autoencoder 50 épocas, limite 0.59, relógio do ledger (mesmo mind.bin
+    // para o mesmo ledger → mesmo fp_hex); ver TreinoConfig::ledger
+    let mut cfg = TreinoConfig::ledger(paths.treino.clone());
+    cfg.caminho_mind_bin = Some(paths.mind.clone());
+    cfg.caminho_resultados = Some(paths.resultados.clone());
+
+    cfg
+}
+
+/// Na subida: reescreve o treino input a partir do ledger e incorpora
+/// todas as linhas na mente (equivale a um treino completo).
+fn load_mind(paths: &Paths) -> Result<TreinoIncremental, String> {
+    // garante diretórios dos arquivos
+    ensure_parent(&paths.treino);
+    ensure_parent(&paths.mind);
+    ensure_parent(&paths.resultados);
+
+    let mut treino = File::create(&paths.treino)
+        .map_err(|e| format!("falha ao criar {}: {}", paths.treino, e))?;
+    let ledger_now = read_file(&paths.ledger);
+    for line in ledger_now.lines() {
+        let l = line.trim();
+        if !l.is_empty() {
+            writeln!(treino, "{}", l).map_err(|e| format!("falha ao escrever {}: {}", paths.treino, e))?;
+        }
+    }
+
+    let mind = TreinoIncremental::a_partir_de_linhas(treino_config(paths), ledger_now.lines());
+    mind.salvar()?;
+    Ok(mind)
+}
+
+/// Roda no thread do writer, logo depois de gravar `head`: nenhuma outra
+/// entrada entra no ledger enquanto a mente é atualizada. Só a linha nova é
+/// incorporada — o mind.bin sai igual ao de um treino completo.
+fn update_mind(
+    paths: &Paths,
+    mind: &mut TreinoIncremental,
+    memoria: &RwLock<Memoria>,
+    head: &LedgerHead,
+    line: &str,
+) -> Result<String, String> {
+    let mut treino = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&paths.treino)
+        .map_err(|e| format!("falha ao abrir {}: {}", paths.treino, e))?;
+    writeln!(treino, "{}", line.trim()).map_err(|e| format!("falha ao escrever {}: {}", paths.treino, e))?;
+
+    let incorporada = mind.incorporar(line);
+    {
+        let mut memoria = memoria.write().unwrap_or_else(|p| p.into_inner());
+        memoria.version = head.version;
+        if let Some(registro) = mind.analise().ultimo_registro().filter(|_| incorporada) {
+            memoria.indice.adicionar(registro);
+        }
+    }
+    mind.salvar().map_err(|e| format!("treino falhou: {}", e))?;
+
+    // FP = hash(mind.bin) + versão
+    Ok(fp_from_mind_and_version(&paths.mind, head.version))
+}
+
+/// Refaz treino input, mente e índice a partir do ledger, como na subida.
+/// Usado quando uma atualização incremental falhou com a entrada já gravada.
+fn resync_mind(
+    paths: &Paths,
+    mind: &mut TreinoIncremental,
+    memoria: &RwLock<Memoria>,
+    head: &LedgerHead,
+) -> Result<String, String> {
+    let nova = load_mind(paths)?;
+    let registros = nova.analise().registros()?;
+    *memoria.write().unwrap_or_else(|p| p.into_inner()) = Memoria {
+        version: head.version,
+        indice: IndiceSimilaridade::new(registros),
+    };
+    *mind = nova;
+
+    Ok(fp_from_mind_and_version(&paths.mind, head.version))
+}
+
+// =========================================================
+// SERVIÇO
+// =========================================================
+
+impl InfoService {
+    /// Abre o ledger (cauda rasgada é recuperada), refaz a mente a partir
+    /// dele e sobe o writer. Ledger quebrado ou mente ilegível → mensagem
+    /// e exit 1: `nome` não sobe.
+    pub fn start(nome: &str, info: &InfoConfig) -> Arc<Self> {
+        let paths = Paths {
+            ledger: info.ledger_path.clone(),
+            treino: info.treino_input.clone(),
+            mind: info.mind_path.clone(),
+            resultados: info.result_path.clone(),
+        };
+        let fsync = info.fsync();
+
+        // 📒 cadeia quebrada = não sobe (ver ledger.rs)
+        let (writer, torn) = LedgerWriter::open(&paths.ledger, fsync).unwrap_or_else(|e| {
+            eprintln!("❌ {}", e);
+            eprintln!("❌ {} não sobe sem um ledger íntegro", nome);
+            process::exit(1);
+        });
+        if torn > 0 {
+            println!("🩹 Ledger: {} bytes de linha rasgada movidos para {}.torn", torn, paths.ledger);
+        }
+        let head = writer.head().clone();
+        println!("📒 Ledger {}: v={} · cabeça {} · fsync {}", paths.ledger, head.version, head.entry, fsync);
+
+        let mut mind = load_mind(&paths).unwrap_or_else(|e| {
+            eprintln!("❌ {}", e);
+            eprintln!("❌ {} não sobe sem a mente", nome);
+            process::exit(1);
+        });
+        println!("🧠 Mente carregada: {} linhas · {}", mind.analise().linhas_processadas, paths.mind);
+
+        let registros = mind.analise().registros().unwrap_or_else(|e| {
+            eprintln!("❌ registros da mente: {}", e);
+            process::exit(1);
+        });
+        let memoria = Arc::new(RwLock::new(Memoria {
+            version: head.version,
+            indice: IndiceSimilaridade::new(registros),
+        }));
+        let publicado = Arc::new(RwLock::new(Publicado {
+            version: head.version,
+            fp_hex: fp_from_mind_and_version(&paths.mind, head.version),
+            ledger_head: head.entry,
+        }));
+        let degradada = Arc::new(AtomicBool::new(false));
+
+        let mind_path = paths.mind.clone();
+        let (w_memoria, w_publicado, w_degradada) = (memoria.clone(), publicado.clone(), degradada.clone());
+        let ledger = LedgerQueue::spawn(writer, move |head, line| {
+            // marcada até dar certo: um panic no meio também deixa a mente suspeita
+            let ressincronizar = w_degradada.swap(true, Ordering::SeqCst);
+            let result = if ressincronizar {
+                resync_mind(&paths, &mut mind, &w_memoria, head)
+            } else {
+                update_mind(&paths, &mut mind, &w_memoria, head, line).or_else(|e| {
+                    eprintln!("⚠️ v={}: {} — refazendo a mente a partir do ledger", head.version, e);
+                    resync_mind(&paths, &mut mind, &w_memoria, head)
+                        .map_err(|r| format!("{}; ressincronização falhou: {}", e, r))
+                })
+            };
+            w_degradada.store(result.is_err(), Ordering::SeqCst);
+
+            // a entrada já está no ledger: falha da mente vira aviso, não erro
+            let mente = match result {
+                Ok(fp_hex) => Mente { fp_hex, warning: None },
+                Err(e) => {
+                    eprintln!("⚠️ v={} gravada, mas a mente não acompanhou: {}", head.version, e);
+                    Mente { fp_hex: fp_from_mind_and_version(&paths.mind, head.version), warning: Some(e) }
+                }
+            };
+            *w_publicado.write().unwrap_or_else(|p| p.into_inner()) = Publicado {
+                version: head.version,
+                ledger_head: head.entry.clone(),
+                fp_hex: mente.fp_hex.clone(),
+            };
+            Ok(mente)
+        });
+
+        Arc::new(Self { memoria, publicado, ledger, degradada, mind: mind_path })
+    }
+
+    /// Grava a entrada e espera a mente. `Err` só quando a entrada não foi
+    /// gravada; falha da mente volta como `degraded` + `warning`.
+    pub async fn append(&self, fields: Vec<(String, String)>) -> Result<StateResponse, String> {
+        let (head, mente) = self.ledger.append(fields).await?;
+        let mente = mente.unwrap_or_else(|e| {
+            // só um panic no writer chega aqui
+            eprintln!("⚠️ v={} gravada, mas a mente não acompanhou: {}", head.version, e);
+            Mente { fp_hex: fp_from_mind_and_version(&self.mind, head.version), warning: Some(e) }
+        });
+
+        Ok(StateResponse {
+            version: head.version,
+            fp_hex: mente.fp_hex,
+            ledger_head: head.entry,
+            degraded: mente.warning.is_some(),
+            warning: mente.warning,
+        })
+    }
+
+    /// Último estado publicado pelo writer.
+    pub fn state(&self) -> StateResponse {
+        let publicado = self.publicado.read().unwrap_or_else(|p| p.into_inner());
+        StateResponse {
+            version: publicado.version,
+            fp_hex: publicado.fp_hex.clone(),
+            ledger_head: publicado.ledger_head.clone(),
+            degraded: self.degradada.load(Ordering::SeqCst),
+            warning: None,
+        }
+    }
+
+    /// Top-k da mente, com a versão a que o índice corresponde.
+    fn consultar(&self, consulta: &str, k: usize) -> (u64, Vec<Similar>) {
+        let memoria = self.memoria.read().unwrap_or_else(|p| p.into_inner());
+        (memoria.version, memoria.indice.consultar(consulta, k))
+    }
+}
+
+// =========================================================
+// HTTP
+// =========================================================
+
+pub fn reply(result: Result<StateResponse, String>) -> warp::reply::WithStatus<warp::reply::Json> {
+    match result {
+        Ok(state) => warp::reply::with_status(warp::reply::json(&state), StatusCode::OK),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({ "error": e })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        }
+    }
+}
+
+/// k ausente → SIMILAR_K; sempre dentro de 1..=SIMILAR_K_MAX.
+fn similar_k(k: Option<usize>) -> usize {
+    k.unwrap_or(SIMILAR_K).clamp(1, SIMILAR_K_MAX)
+}
+
+pub async fn similar_handler(query: SimilarQuery, shared: Arc<InfoService>) -> Result<impl warp::Reply, warp::Rejection> {
+    let q = query.q.trim().to_string();
+    if q.is_empty() {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "error": "q vazio" })),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let (version, hits) = shared.consultar(&q, similar_k(query.k));
+    let results = hits
+        .into_iter()
+        .map(|s| SimilarHit {
+            index: s.indice,
+            text: s.texto,
+            timestamp_ns: s.timestamp_ns,
+            similarity_sha256: s.similaridade_sha256,
+            similarity_bytes: s.similaridade_bytes,
+            similarity_combined: s.similaridade_combinada,
+        })
+        .collect();
+
+    Ok(warp::reply::with_status(
+        warp::reply::json(&SimilarResponse { version, query: q, results }),
+        StatusCode::OK,
+    ))
+}
+
+pub async fn state_handler(shared: Arc<InfoService>) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&shared.state()))
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn info(nome: &str) -> InfoConfig {
+        let dir = std::env::temp_dir().join(format!("td_info_{}_{}", std::process::id(), nome));
+        let _ = std::fs::remove_dir_all(&dir);
+        let arquivo = |f: &str| dir.join(f).to_string_lossy().into_owned();
+        InfoConfig {
+            listen: "127.0.0.1:0".into(),
+            tls: None,
+            ledger_path: arquivo("ledger.log"),
+            treino_input: arquivo("treino_input.txt"),
+            mind_path: arquivo("mind.bin"),
+            result_path: arquivo("resultados.txt"),
+            ledger_fsync: "always".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn state_publica_o_fp_da_mente_gravada() {
+        let info = info("state");
+        let service = InfoService::start("teste", &info);
+
+        for hash in ["aa11", "bb22"] {
+            let fields = vec![("hash".to_string(), hash.to_string())];
+            let resposta = service.append(fields).await.unwrap();
+            assert!(!resposta.degraded);
+            assert_eq!(resposta.fp_hex, fp_from_mind_and_version(&info.mind_path, resposta.version));
+        }
+
+        let state = service.state();
+        assert_eq!(state.version, 2);
+        assert_eq!(state.fp_hex, fp_from_mind_and_version(&info.mind_path, 2));
+        assert_eq!(state.ledger_head, service.ledger.head().entry);
+        assert!(!Path::new(&format!("{}.tmp", info.mind_path)).exists());
+
+        // subir de novo sobre o mesmo ledger dá o mesmo fp_hex
+        let reaberto = InfoService::start("teste", &info);
+        assert_eq!(reaberto.state().fp_hex, state.fp_hex);
+
+        // o índice do /similar acompanha as entradas, na versão publicada
+        let (version, hits) = reaberto.consultar("aa11", 5);
+        assert_eq!((version, hits.len()), (2, 2));
+    }
+
+    #[test]
+    fn k_do_similar_fica_entre_1_e_o_maximo() {
+        assert_eq!(similar_k(None), SIMILAR_K);
+        assert_eq!(similar_k(Some(0)), 1);
+        assert_eq!(similar_k(Some(7)), 7);
+        assert_eq!(similar_k(Some(10_000)), SIMILAR_K_MAX);
+    }
+}
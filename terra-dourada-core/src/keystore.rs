@@ -0,0 +1,521 @@
+// =========================================================
+// 🔑 KEYSTORE — chaves Dilithium2 persistentes, cifradas e rotativas
+// =========================================================
+//
+// Arquivo: {TD_KEYSTORE_DIR}/keystore.json
+//
+//   kdf:  Argon2id(passphrase, salt) → KEK de 32 bytes (só em memória)
+//   keys: cada par Dilithium2 cifrado com ChaCha20-Poly1305(KEK),
+//         nonce próprio e AAD = key_id (não dá para trocar blobs de lugar)
+//
+//   key_id = "dil2-" || hex(SHA-256(public_key))[..16]
+//   hmac: chave do DK-PQC-HMAC, cifrada igual (AAD = "td.hmac"); gerada
+//         no primeiro uso e mantida entre restarts
+//
+// Rotação (TD_KEY_ROTATION_SECS / TD_KEY_OVERLAP_SECS):
+//
+//   chave N     [valid_from ─────────────── valid_until)
+//   chave N+1                  ├─ overlap ─┤[valid_from ──────── ...
+//                              publicada    passa a assinar
+//
+// A sucessora é gerada e publicada em GET /keys `overlap` segundos antes
+// de assinar, então verificadores já a conhecem quando a primeira prova
+// com ela aparece. Chaves antigas continuam publicadas para sempre (o
+// key_id de cada prova aponta para elas); os segredos delas saem da
+// memória assim que expiram.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::{general_purpose, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use crystals_dilithium::dilithium2::{Keypair, PUBLICKEYBYTES};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use zeroize::{Zeroize, Zeroizing};
+
+pub const ALGORITHM: &str = "dilithium2";
+const KEYSTORE_VERSION: u32 = 1;
+const KEYSTORE_FILE: &str = "keystore.json";
+const HMAC_AAD: &[u8] = b"td.hmac";
+const HMAC_KEY_LEN: usize = 32;
+
+pub const DEFAULT_ROTATION_SECS: u64 = 30 * 24 * 3600;
+pub const DEFAULT_OVERLAP_SECS: u64 = 24 * 3600;
+
+// =========================================================
+// ARQUIVO
+// =========================================================
+
+#[derive(Serialize, Deserialize, Clone)]
+struct KdfParams {
+    alg: String,
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct KeyRecord {
+    key_id: String,
+    algorithm: String,
+    public_key: String,
+    created_at: u64,
+    valid_from: u64,
+    valid_until: u64,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct SealedSecret {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    kdf: KdfParams,
+    keys: Vec<KeyRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hmac: Option<SealedSecret>,
+}
+
+// =========================================================
+// VISÃO PÚBLICA (GET /keys)
+// =========================================================
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStatus {
+    /// Publicada, ainda não assina (janela de overlap).
+    Next,
+    Active,
+    Retired,
+}
+
+#[derive(Serialize, Clone)]
+pub struct PublicKeyInfo {
+    pub key_id: String,
+    pub algorithm: String,
+    pub public_key: String,
+    pub valid_from: u64,
+    pub valid_until: u64,
+    pub status: KeyStatus,
+}
+
+// =========================================================
+// CHAVE DE ASSINATURA (em memória)
+// =========================================================
+
+/// Par Dilithium2 decifrado. O segredo é zerado no drop.
+pub struct SigningKey {
+    pub key_id: String,
+    keypair: Keypair,
+}
+
+impl SigningKey {
+    pub fn keypair(&self) -> &Keypair {
+        &self.keypair
+    }
+}
+
+impl Drop for SigningKey {
+    fn drop(&mut self) {
+        self.keypair.secret.bytes.zeroize();
+    }
+}
+
+pub fn key_id_for(public_key: &[u8]) -> String {
+    format!("dil2-{}", &hex::encode(Sha256::digest(public_key))[..16])
+}
+
+#[derive(Clone, Copy)]
+pub struct RotationPolicy {
+    pub interval: u64,
+    pub overlap: u64,
+}
+
+// =========================================================
+// KEYSTORE
+// =========================================================
+
+pub struct KeyStore {
+    path: PathBuf,
+    file: KeystoreFile,
+    kek: Zeroizing<[u8; 32]>,
+    signers: HashMap<String, Arc<SigningKey>>,
+    policy: RotationPolicy,
+}
+
+fn derive_kek(passphrase: &[u8], kdf: &KdfParams) -> Result<Zeroizing<[u8; 32]>, String> {
+    if kdf.alg != "argon2id" {
+        return Err(format!("kdf desconhecido: {}", kdf.alg));
+    }
+    let salt = hex::decode(&kdf.salt).map_err(|e| format!("salt inválido: {}", e))?;
+    let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32))
+        .map_err(|e| format!("parâmetros argon2 inválidos: {}", e))?;
+
+    let mut kek = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase, &salt, kek.as_mut())
+        .map_err(|e| format!("falha no argon2: {}", e))?;
+    Ok(kek)
+}
+
+impl KeyStore {
+    /// Abre (ou cria) o keystore em `dir`. Uma passphrase errada falha
+    /// aqui, na decifragem da chave mais recente — nunca gera chave nova
+    /// cifrada com a KEK errada.
+    pub fn open(dir: &Path, passphrase: &[u8], policy: RotationPolicy, now: u64) -> Result<Self, String> {
+        if policy.interval == 0 || policy.overlap >= policy.interval {
+            return Err("rotação exige 0 < overlap < intervalo".into());
+        }
+        if passphrase.is_empty() {
+            return Err("passphrase do keystore vazia".into());
+        }
+
+        fs::create_dir_all(dir).map_err(|e| format!("falha create_dir_all({:?}): {}", dir, e))?;
+        let path = dir.join(KEYSTORE_FILE);
+
+        let file = if path.exists() {
+            let txt = fs::read_to_string(&path).map_err(|e| format!("falha ao ler {:?}: {}", path, e))?;
+            let file: KeystoreFile =
+                serde_json::from_str(&txt).map_err(|e| format!("keystore corrompido {:?}: {}", path, e))?;
+            if file.version != KEYSTORE_VERSION {
+                return Err(format!("versão de keystore não suportada: {}", file.version));
+            }
+            file
+        } else {
+            let mut salt = [0u8; 16];
+            rand::thread_rng().fill_bytes(&mut salt);
+            let params = Params::default();
+            KeystoreFile {
+                version: KEYSTORE_VERSION,
+                kdf: KdfParams {
+                    alg: "argon2id".into(),
+                    salt: hex::encode(salt),
+                    m_cost: params.m_cost(),
+                    t_cost: params.t_cost(),
+                    p_cost: params.p_cost(),
+                },
+                keys: Vec::new(),
+                hmac: None,
+            }
+        };
+
+        let kek = derive_kek(passphrase, &file.kdf)?;
+        let mut store = Self { path, file, kek, signers: HashMap::new(), policy };
+
+        if let Some(last) = store.file.keys.last().cloned() {
+            store.unseal(&last)?;
+        }
+        for rec in store.file.keys.clone() {
+            if rec.valid_until > now && !store.signers.contains_key(&rec.key_id) {
+                store.unseal(&rec)?;
+            }
+        }
+
+        store.rotate(now)?;
+        Ok(store)
+    }
+
+    fn unseal(&mut self, rec: &KeyRecord) -> Result<(), String> {
+        let nonce = hex::decode(&rec.nonce).map_err(|e| format!("{}: nonce inválido: {}", rec.key_id, e))?;
+        let ct = general_purpose::STANDARD
+            .decode(&rec.ciphertext)
+            .map_err(|e| format!("{}: ciphertext inválido: {}", rec.key_id, e))?;
+        if nonce.len() != 12 {
+            return Err(format!("{}: nonce com tamanho errado", rec.key_id));
+        }
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(self.kek.as_ref()));
+        let plain = Zeroizing::new(
+            cipher
+                .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ct, aad: rec.key_id.as_bytes() })
+                .map_err(|_| format!("{}: passphrase incorreta ou keystore adulterado", rec.key_id))?,
+        );
+        if plain.len() != PUBLICKEYBYTES + crystals_dilithium::dilithium2::SECRETKEYBYTES {
+            return Err(format!("{}: par de chaves com tamanho errado", rec.key_id));
+        }
+
+        let keypair = Keypair::from_bytes(&plain);
+        let public = general_purpose::STANDARD.encode(keypair.public.to_bytes());
+        if public != rec.public_key || key_id_for(&keypair.public.to_bytes()) != rec.key_id {
+            return Err(format!("{}: chave pública não confere com o segredo", rec.key_id));
+        }
+
+        self.signers
+            .insert(rec.key_id.clone(), Arc::new(SigningKey { key_id: rec.key_id.clone(), keypair }));
+        Ok(())
+    }
+
+    fn generate(&mut self, now: u64, valid_from: u64) -> Result<String, String> {
+        let keypair = Keypair::generate(None);
+        let public = keypair.public.to_bytes();
+        let key_id = key_id_for(&public);
+
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let plain = Zeroizing::new(keypair.to_bytes());
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(self.kek.as_ref()));
+        let ct = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: plain.as_ref(), aad: key_id.as_bytes() })
+            .map_err(|_| "falha ao cifrar chave".to_string())?;
+
+        self.file.keys.push(KeyRecord {
+            key_id: key_id.clone(),
+            algorithm: ALGORITHM.into(),
+            public_key: general_purpose::STANDARD.encode(public),
+            created_at: now,
+            valid_from,
+            valid_until: valid_from + self.policy.interval,
+            nonce: hex::encode(nonce),
+            ciphertext: general_purpose::STANDARD.encode(ct),
+        });
+        self.signers
+            .insert(key_id.clone(), Arc::new(SigningKey { key_id: key_id.clone(), keypair }));
+        Ok(key_id)
+    }
+
+    /// Chave do DK-PQC-HMAC. Gerada e persistida (cifrada com a KEK) na
+    /// primeira chamada; depois, a mesma a cada restart.
+    pub fn hmac_key(&mut self) -> Result<Zeroizing<Vec<u8>>, String> {
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(self.kek.as_ref()));
+
+        if let Some(sealed) = &self.file.hmac {
+            let nonce = hex::decode(&sealed.nonce).map_err(|e| format!("hmac: nonce inválido: {}", e))?;
+            let ct = general_purpose::STANDARD
+                .decode(&sealed.ciphertext)
+                .map_err(|e| format!("hmac: ciphertext inválido: {}", e))?;
+            if nonce.len() != 12 {
+                return Err("hmac: nonce com tamanho errado".into());
+            }
+            let key = Zeroizing::new(
+                cipher
+                    .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ct, aad: HMAC_AAD })
+                    .map_err(|_| "hmac: passphrase incorreta ou keystore adulterado".to_string())?,
+            );
+            if key.len() != HMAC_KEY_LEN {
+                return Err("hmac: chave com tamanho errado".into());
+            }
+            return Ok(key);
+        }
+
+        let mut key = Zeroizing::new(vec![0u8; HMAC_KEY_LEN]);
+        rand::thread_rng().fill_bytes(&mut key);
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ct = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: key.as_slice(), aad: HMAC_AAD })
+            .map_err(|_| "falha ao cifrar chave hmac".to_string())?;
+
+        self.file.hmac = Some(SealedSecret {
+            nonce: hex::encode(nonce),
+            ciphertext: general_purpose::STANDARD.encode(ct),
+        });
+        if let Err(e) = self.persist() {
+            // sem persistir, a tag mudaria no restart
+            self.file.hmac = None;
+            return Err(e);
+        }
+        Ok(key)
+    }
+
+    /// Grava em temporário + rename (nunca deixa o keystore pela metade).
+    fn persist(&self) -> Result<(), String> {
+        let tmp = self.path.with_extension("json.tmp");
+        let bytes = serde_json::to_vec_pretty(&self.file)
+            .map_err(|e| format!("falha serializando keystore: {}", e))?;
+
+        let mut opts = OpenOptions::new();
+        opts.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.mode(0o600);
+        }
+        let mut f = opts.open(&tmp).map_err(|e| format!("falha ao abrir {:?}: {}", tmp, e))?;
+        f.write_all(&bytes).map_err(|e| format!("falha ao escrever keystore: {}", e))?;
+        f.sync_all().map_err(|e| format!("falha no fsync: {}", e))?;
+        fs::rename(&tmp, &self.path).map_err(|e| format!("falha ao renomear keystore: {}", e))
+    }
+
+    fn active_record(&self, now: u64) -> Option<&KeyRecord> {
+        self.file
+            .keys
+            .iter()
+            .filter(|k| k.valid_from <= now && now < k.valid_until)
+            .max_by_key(|k| k.valid_from)
+    }
+
+    /// Garante chave ativa e, dentro do overlap, a sucessora publicada.
+    /// Devolve os key_ids gerados (vazio = nada mudou).
+    pub fn rotate(&mut self, now: u64) -> Result<Vec<String>, String> {
+        let mut created = Vec::new();
+
+        let active_until = match self.active_record(now) {
+            Some(k) => k.valid_until,
+            None => {
+                // keystore novo, ou servidor parado além da validade da última chave
+                created.push(self.generate(now, now)?);
+                now + self.policy.interval
+            }
+        };
+
+        let has_next = self.file.keys.iter().any(|k| k.valid_from >= active_until);
+        if !has_next && now + self.policy.overlap >= active_until {
+            created.push(self.generate(now, active_until)?);
+        }
+
+        if !created.is_empty() {
+            if let Err(e) = self.persist() {
+                // sem persistir, não assina com chave que sumiria no restart
+                for id in &created {
+                    self.signers.remove(id);
+                }
+                self.file.keys.retain(|k| !created.contains(&k.key_id));
+                return Err(e);
+            }
+        }
+
+        // segredos expirados saem da memória (zerados no drop)
+        let expired: Vec<String> = self
+            .file
+            .keys
+            .iter()
+            .filter(|k| k.valid_until <= now)
+            .map(|k| k.key_id.clone())
+            .collect();
+        for id in expired {
+            self.signers.remove(&id);
+        }
+
+        Ok(created)
+    }
+
+    /// Chave que assina em `now` (roda a rotação antes, se preciso).
+    pub fn signer(&mut self, now: u64) -> Result<Arc<SigningKey>, String> {
+        self.rotate(now)?;
+        let id = self
+            .active_record(now)
+            .map(|k| k.key_id.clone())
+            .ok_or_else(|| "nenhuma chave ativa".to_string())?;
+        self.signers
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| format!("{}: segredo não carregado", id))
+    }
+
+    pub fn active_key_id(&self, now: u64) -> Option<&str> {
+        self.active_record(now).map(|k| k.key_id.as_str())
+    }
+
+    /// Todas as chaves já usadas ou anunciadas, mais antiga primeiro.
+    pub fn public_keys(&self, now: u64) -> Vec<PublicKeyInfo> {
+        self.file
+            .keys
+            .iter()
+            .map(|k| PublicKeyInfo {
+                key_id: k.key_id.clone(),
+                algorithm: k.algorithm.clone(),
+                public_key: k.public_key.clone(),
+                valid_from: k.valid_from,
+                valid_until: k.valid_until,
+                status: if now < k.valid_from {
+                    KeyStatus::Next
+                } else if now < k.valid_until {
+                    KeyStatus::Active
+                } else {
+                    KeyStatus::Retired
+                },
+            })
+            .collect()
+    }
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLITICA: RotationPolicy = RotationPolicy { interval: 100, overlap: 20 };
+
+    fn dir(nome: &str) -> PathBuf {
+        let mut p = std::env::temp_dir();
+        p.push(format!("keystore_{}_{}", std::process::id(), nome));
+        let _ = fs::remove_dir_all(&p);
+        p
+    }
+
+    #[test]
+    fn passphrase_errada_nao_abre() {
+        let d = dir("passphrase");
+        let store = KeyStore::open(&d, b"certa", POLITICA, 1_000).unwrap();
+        let id = store.active_key_id(1_000).unwrap().to_string();
+        let antes = fs::read(d.join(KEYSTORE_FILE)).unwrap();
+
+        let err = KeyStore::open(&d, b"errada", POLITICA, 1_000).err().unwrap();
+        assert!(err.contains("passphrase incorreta"), "{}", err);
+        // nada foi gerado nem regravado com a KEK errada
+        assert_eq!(fs::read(d.join(KEYSTORE_FILE)).unwrap(), antes);
+
+        let store = KeyStore::open(&d, b"certa", POLITICA, 1_000).unwrap();
+        assert_eq!(store.active_key_id(1_000), Some(id.as_str()));
+        let _ = fs::remove_dir_all(&d);
+    }
+
+    #[test]
+    fn rotacao_publica_a_sucessora_antes_de_assinar() {
+        let d = dir("rotacao");
+        let mut store = KeyStore::open(&d, b"certa", POLITICA, 1_000).unwrap();
+        let a = store.signer(1_000).unwrap().key_id.clone();
+
+        // fora do overlap, nada muda
+        assert!(store.rotate(1_079).unwrap().is_empty());
+
+        // dentro do overlap: sucessora publicada, antiga ainda assina
+        let criadas = store.rotate(1_080).unwrap();
+        assert_eq!(criadas.len(), 1);
+        let b = criadas[0].clone();
+        let status: Vec<_> = store.public_keys(1_080).iter().map(|k| (k.key_id.clone(), k.status)).collect();
+        assert_eq!(status, vec![(a.clone(), KeyStatus::Active), (b.clone(), KeyStatus::Next)]);
+        assert_eq!(store.signer(1_099).unwrap().key_id, a);
+
+        // a sucessora sobrevive ao restart e assume no fim da validade
+        let mut store = KeyStore::open(&d, b"certa", POLITICA, 1_099).unwrap();
+        assert_eq!(store.signer(1_100).unwrap().key_id, b);
+        let status: Vec<_> = store.public_keys(1_100).iter().map(|k| k.status).collect();
+        assert_eq!(status, vec![KeyStatus::Retired, KeyStatus::Active]);
+        // segredo da chave aposentada sai da memória
+        assert!(!store.signers.contains_key(&a));
+        let _ = fs::remove_dir_all(&d);
+    }
+
+    #[test]
+    fn chave_hmac_sobrevive_ao_restart() {
+        let d = dir("hmac");
+        let mut store = KeyStore::open(&d, b"certa", POLITICA, 1_000).unwrap();
+        let k1 = store.hmac_key().unwrap();
+        assert_eq!(k1.len(), HMAC_KEY_LEN);
+        assert_eq!(store.hmac_key().unwrap(), k1);
+
+        let mut store = KeyStore::open(&d, b"certa", POLITICA, 1_050).unwrap();
+        assert_eq!(store.hmac_key().unwrap(), k1);
+        // em disco só o ciphertext
+        let txt = fs::read_to_string(d.join(KEYSTORE_FILE)).unwrap();
+        assert!(!txt.contains(&hex::encode(k1.as_slice())));
+        let _ = fs::remove_dir_all(&d);
+    }
+}
@@ -0,0 +1,615 @@
+// =========================================================
+// 📒 LEDGER — ledgers encadeados do info_local/info_global
+// =========================================================
+//
+// Uma entrada por linha, campos `chave=valor` separados por `|`:
+//
+//   v=<n>|<campos do serviço>|prev=<entry anterior>|entry=<hash desta>
+//
+//   v      1, 2, 3, ... sem buracos
+//   prev   `entry` da linha anterior (64 zeros na primeira)
+//   entry  hex(SHA-256("td.ledger.v1:" || JCS(objeto)))
+//          objeto = todos os campos da linha menos `entry`, como strings
+//          (JCS = JSON canônico, RFC 8785 — ver canonical.rs)
+//
+// Editar, apagar, inserir ou reordenar linhas quebra `prev` ou `entry`
+// a partir do ponto alterado; `verify_ledger` aponta a primeira linha
+// quebrada. Só reescrever o ledger inteiro passa despercebido — por isso
+// o `entry` da cabeça é o que o serviço publica/compara.
+//
+// FP da mente = SHA-256(mind.bin || versão u64 LE), em hex.
+
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use tokio::sync::oneshot;
+
+use crate::canonical::canonicalize;
+
+pub const ENTRY_DOMAIN: &str = "td.ledger.v1";
+pub const GENESIS_PREV: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Campos que o próprio ledger controla; o serviço não pode usá-los.
+const RESERVED: [&str; 3] = ["v", "prev", "entry"];
+
+// =========================================================
+// ERROS
+// =========================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerBreak {
+    /// Linha sem o formato `chave=valor|...` ou sem v/prev/entry.
+    Malformed(String),
+    /// `v` fora de sequência (linha apagada, inserida ou reordenada).
+    Version { expected: u64, found: String },
+    /// `prev` não aponta para o `entry` da linha anterior.
+    Prev { expected: String, found: String },
+    /// `entry` não confere com o conteúdo da linha (linha editada).
+    Entry { expected: String, found: String },
+}
+
+/// Primeiro elo quebrado: número da linha (a partir de 1) e o motivo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerError {
+    pub line: usize,
+    pub kind: LedgerBreak,
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ledger quebrado na linha {}: ", self.line)?;
+        match &self.kind {
+            LedgerBreak::Malformed(msg) => write!(f, "{}", msg),
+            LedgerBreak::Version { expected, found } => {
+                write!(f, "v={} fora de sequência (esperado v={})", found, expected)
+            }
+            LedgerBreak::Prev { expected, found } => {
+                write!(f, "prev={} não encadeia (esperado {})", found, expected)
+            }
+            LedgerBreak::Entry { expected, found } => {
+                write!(f, "entry={} não confere com o conteúdo (calculado {})", found, expected)
+            }
+        }
+    }
+}
+
+/// Estado da cabeça do ledger depois de verificado.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerHead {
+    pub version: u64,
+    pub entry: String,
+}
+
+impl LedgerHead {
+    pub fn genesis() -> Self {
+        Self { version: 0, entry: GENESIS_PREV.to_string() }
+    }
+}
+
+// =========================================================
+// FORMATO
+// =========================================================
+
+/// Valor seguro para uma linha do ledger (sem quebras de linha nem `|`).
+pub fn clean_field(s: &str) -> String {
+    s.replace(['\n', '\r'], " ")
+        .replace('|', "_")
+        .trim()
+        .to_string()
+}
+
+/// hex(SHA-256("td.ledger.v1:" || JCS({campo: valor, ...}))).
+pub fn entry_hash(fields: &[(&str, &str)]) -> String {
+    let mut obj = Map::new();
+    for (k, v) in fields {
+        obj.insert((*k).to_string(), Value::String((*v).to_string()));
+    }
+    // só strings: a canonicalização não tem como falhar
+    let canonical = canonicalize(&Value::Object(obj)).expect("JCS de strings");
+
+    let mut h = Sha256::new();
+    h.update(ENTRY_DOMAIN.as_bytes());
+    h.update(b":");
+    h.update(canonical.as_bytes());
+    hex::encode(h.finalize())
+}
+
+/// Monta a linha `v=..|campos..|prev=..|entry=..` (sem `\n`) e devolve
+/// junto o `entry` dela. Os valores passam por `clean_field`.
+pub fn format_entry(version: u64, prev: &str, fields: &[(&str, &str)]) -> Result<(String, String), String> {
+    let v = version.to_string();
+    let mut all: Vec<(&str, String)> = vec![("v", v)];
+    for (k, val) in fields {
+        if RESERVED.contains(k) || k.is_empty() || k.contains(['=', '|', '\n']) {
+            return Err(format!("campo de ledger inválido: {:?}", k));
+        }
+        if all.iter().any(|(existing, _)| existing == k) {
+            return Err(format!("campo de ledger repetido: {:?}", k));
+        }
+        all.push((k, clean_field(val)));
+    }
+    all.push(("prev", prev.to_string()));
+
+    let refs: Vec<(&str, &str)> = all.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    let entry = entry_hash(&refs);
+
+    let mut line: Vec<String> = all.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    line.push(format!("entry={}", entry));
+    Ok((line.join("|"), entry))
+}
+
+fn parse_line(line: &str) -> Result<Vec<(&str, &str)>, String> {
+    let mut fields: Vec<(&str, &str)> = Vec::new();
+    for part in line.split('|') {
+        let (k, v) = part
+            .split_once('=')
+            .ok_or_else(|| format!("campo sem '=': {:?}", part))?;
+        if fields.iter().any(|(existing, _)| *existing == k) {
+            return Err(format!("campo repetido: {:?}", k));
+        }
+        fields.push((k, v));
+    }
+    Ok(fields)
+}
+
+// =========================================================
+// VERIFICAÇÃO
+// =========================================================
+
+/// Percorre o ledger inteiro e devolve a cabeça, ou o primeiro elo
+/// quebrado. Ledger vazio → versão 0 com prev gênese.
+pub fn verify_ledger(text: &str) -> Result<LedgerHead, LedgerError> {
+    let mut head = LedgerHead::genesis();
+
+    for (idx, line) in text.lines().enumerate() {
+        let fail = |kind| LedgerError { line: idx + 1, kind };
+        let expected_v = head.version + 1;
+
+        if line.trim().is_empty() {
+            return Err(fail(LedgerBreak::Malformed("linha vazia".into())));
+        }
+        let fields = parse_line(line).map_err(|e| fail(LedgerBreak::Malformed(e)))?;
+
+        // v primeiro, entry por último, prev logo antes de entry
+        let n = fields.len();
+        if n < 3 || fields[0].0 != "v" || fields[n - 2].0 != "prev" || fields[n - 1].0 != "entry" {
+            return Err(fail(LedgerBreak::Malformed(
+                "esperado v=..|...|prev=..|entry=.. (formato antigo sem encadeamento?)".into(),
+            )));
+        }
+        if fields[1..n - 2].iter().any(|(k, _)| RESERVED.contains(k)) {
+            return Err(fail(LedgerBreak::Malformed("campo reservado fora de posição".into())));
+        }
+
+        let found_v = fields[0].1;
+        if found_v != expected_v.to_string() {
+            return Err(fail(LedgerBreak::Version { expected: expected_v, found: found_v.into() }));
+        }
+
+        let prev = fields[n - 2].1;
+        if prev != head.entry {
+            return Err(fail(LedgerBreak::Prev { expected: head.entry.clone(), found: prev.into() }));
+        }
+
+        let entry = fields[n - 1].1;
+        let computed = entry_hash(&fields[..n - 1]);
+        if entry != computed {
+            return Err(fail(LedgerBreak::Entry { expected: computed, found: entry.into() }));
+        }
+
+        head = LedgerHead { version: expected_v, entry: computed };
+    }
+
+    Ok(head)
+}
+
+/// Lê e verifica o ledger do disco (arquivo ausente = ledger vazio).
+pub fn verify_ledger_file(path: &str) -> Result<LedgerHead, String> {
+    verify_ledger(&read_file(path)).map_err(|e| format!("{}: {}", path, e))
+}
+
+// =========================================================
+// WRITER (um só escritor por ledger)
+// =========================================================
+//
+// Todos os appends passam por uma fila e um único thread dono do arquivo:
+// a versão vem da cabeça em memória, então duas requisições simultâneas
+// nunca recebem o mesmo `v`.
+//
+// Na abertura, bytes depois do último `\n` são uma linha rasgada por
+// crash no meio da escrita: saem do ledger e vão para `<ledger>.torn`
+// (para auditoria) antes da verificação da cadeia. Se uma escrita falha,
+// o arquivo volta ao tamanho anterior e a cabeça não avança.
+
+/// Quando o writer faz fsync do ledger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// fsync a cada entrada (padrão): entrada respondida = entrada em disco.
+    Always,
+    /// fsync a cada N entradas; um crash pode perder até N-1 respondidas.
+    Every(u32),
+    /// Deixa para o sistema operacional.
+    Never,
+}
+
+impl FsyncPolicy {
+    /// "always" | "never" | N (fsync a cada N entradas)
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim() {
+            "always" => Ok(FsyncPolicy::Always),
+            "never" => Ok(FsyncPolicy::Never),
+            n => match n.parse::<u32>() {
+                Ok(0) | Err(_) => Err(format!("fsync inválido {:?} (use always, never ou N > 0)", s)),
+                Ok(1) => Ok(FsyncPolicy::Always),
+                Ok(n) => Ok(FsyncPolicy::Every(n)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for FsyncPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsyncPolicy::Always => write!(f, "always"),
+            FsyncPolicy::Every(n) => write!(f, "a cada {} entradas", n),
+            FsyncPolicy::Never => write!(f, "never"),
+        }
+    }
+}
+
+pub struct LedgerWriter {
+    path: String,
+    file: File,
+    len: u64,
+    head: LedgerHead,
+    policy: FsyncPolicy,
+    unsynced: u32,
+}
+
+impl LedgerWriter {
+    /// Abre (ou cria) o ledger, recupera a cauda rasgada e verifica a
+    /// cadeia. Devolve também quantos bytes rasgados foram removidos.
+    pub fn open(path: &str, policy: FsyncPolicy) -> Result<(Self, u64), String> {
+        ensure_parent(path);
+        let created = !Path::new(path).exists();
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("falha ao abrir {}: {}", path, e))?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(|e| format!("falha ao ler {}: {}", path, e))?;
+
+        let keep = bytes.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+        let torn = (bytes.len() - keep) as u64;
+        if torn > 0 {
+            let torn_path = format!("{}.torn", path);
+            let mut aside = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&torn_path)
+                .map_err(|e| format!("falha ao abrir {}: {}", torn_path, e))?;
+            aside
+                .write_all(&bytes[keep..])
+                .and_then(|_| aside.write_all(b"\n"))
+                .and_then(|_| aside.sync_all())
+                .map_err(|e| format!("falha ao gravar {}: {}", torn_path, e))?;
+
+            file.set_len(keep as u64).map_err(|e| format!("falha ao truncar {}: {}", path, e))?;
+            file.sync_all().map_err(|e| format!("falha no fsync de {}: {}", path, e))?;
+        }
+
+        let text = std::str::from_utf8(&bytes[..keep]).map_err(|e| format!("{}: não é UTF-8: {}", path, e))?;
+        let head = verify_ledger(text).map_err(|e| format!("{}: {}", path, e))?;
+
+        if created {
+            sync_parent_dir(path);
+        }
+
+        let writer = Self { path: path.to_string(), file, len: keep as u64, head, policy, unsynced: 0 };
+        Ok((writer, torn))
+    }
+
+    pub fn head(&self) -> &LedgerHead {
+        &self.head
+    }
+
+    /// Grava a próxima entrada; só avança a cabeça se a linha inteira
+    /// chegou ao arquivo (e ao disco, conforme a política de fsync).
+    /// Devolve a cabeça nova e a linha gravada, sem `\n`.
+    pub fn append(&mut self, fields: &[(&str, &str)]) -> Result<(LedgerHead, String), String> {
+        let version = self.head.version + 1;
+        let (mut line, entry) = format_entry(version, &self.head.entry, fields)?;
+        line.push('\n');
+
+        let sync = match self.policy {
+            FsyncPolicy::Always => true,
+            FsyncPolicy::Every(n) => self.unsynced + 1 >= n,
+            FsyncPolicy::Never => false,
+        };
+
+        let written = self.file.write_all(line.as_bytes()).and_then(|_| {
+            if sync {
+                self.file.sync_data()
+            } else {
+                Ok(())
+            }
+        });
+
+        if let Err(e) = written {
+            // desfaz a linha parcial para não deixar cauda rasgada
+            let _ = self.file.set_len(self.len);
+            return Err(format!("falha ao gravar v={} em {}: {}", version, self.path, e));
+        }
+
+        self.len += line.len() as u64;
+        self.unsynced = if sync { 0 } else { self.unsynced + 1 };
+        self.head = LedgerHead { version, entry };
+        line.pop();
+        Ok((self.head.clone(), line))
+    }
+}
+
+fn sync_parent_dir(path: &str) {
+    #[cfg(unix)]
+    if let Some(dir) = Path::new(path).parent() {
+        let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        if let Ok(d) = File::open(dir) {
+            let _ = d.sync_all();
+        }
+    }
+}
+
+/// Cabeça gravada + resultado do `after_append`.
+pub type Appended<T> = (LedgerHead, Result<T, String>);
+
+type Job<T> = (Vec<(String, String)>, oneshot::Sender<Result<Appended<T>, String>>);
+
+/// Fila de appends na frente de um `LedgerWriter`, com um thread dedicado.
+///
+/// `after_append` roda no mesmo thread logo depois de cada entrada gravada,
+/// recebendo a cabeça nova e a linha gravada (sem `\n`) — ex.: incorporar
+/// a linha na mente —, então o resultado dele corresponde exatamente à
+/// versão recém-gravada. Se ele falhar, a entrada continua gravada: o erro
+/// volta junto com a cabeça, não no lugar dela.
+pub struct LedgerQueue<T> {
+    tx: mpsc::Sender<Job<T>>,
+    head: Arc<Mutex<LedgerHead>>,
+}
+
+impl<T> Clone for LedgerQueue<T> {
+    fn clone(&self) -> Self {
+        Self { tx: self.tx.clone(), head: self.head.clone() }
+    }
+}
+
+impl<T: Send + 'static> LedgerQueue<T> {
+    pub fn spawn<F>(mut writer: LedgerWriter, mut after_append: F) -> Self
+    where
+        F: FnMut(&LedgerHead, &str) -> Result<T, String> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel::<Job<T>>();
+        let head = Arc::new(Mutex::new(writer.head().clone()));
+        let shared_head = head.clone();
+
+        thread::spawn(move || {
+            for (fields, reply) in rx {
+                let refs: Vec<(&str, &str)> = fields.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+                let result = writer.append(&refs).map(|(new_head, line)| {
+                    *shared_head.lock().unwrap_or_else(|p| p.into_inner()) = new_head.clone();
+
+                    // um panic no pós-processamento não pode derrubar o writer
+                    let out = panic::catch_unwind(AssertUnwindSafe(|| after_append(&new_head, &line)))
+                        .unwrap_or_else(|_| Err("panic no pós-processamento".into()));
+                    (new_head, out)
+                });
+
+                let _ = reply.send(result);
+            }
+        });
+
+        Self { tx, head }
+    }
+
+    /// Enfileira a entrada e espera o writer. `Err` só quando a entrada não
+    /// foi gravada.
+    pub async fn append(&self, fields: Vec<(String, String)>) -> Result<Appended<T>, String> {
+        let (reply, rx) = oneshot::channel();
+        self.tx
+            .send((fields, reply))
+            .map_err(|_| "writer do ledger parado".to_string())?;
+        rx.await.map_err(|_| "writer do ledger parado".to_string())?
+    }
+
+    /// Última entrada gravada.
+    pub fn head(&self) -> LedgerHead {
+        self.head.lock().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+}
+
+// =========================================================
+// ARQUIVOS
+// =========================================================
+
+/// Conteúdo do arquivo, ou "" se ele ainda não existe.
+pub fn read_file(path: &str) -> String {
+    if !Path::new(path).exists() {
+        return String::new();
+    }
+    let mut s = String::new();
+    File::open(path).unwrap().read_to_string(&mut s).unwrap();
+    s
+}
+
+pub fn ensure_parent(path: &str) {
+    if let Some(p) = Path::new(path).parent() {
+        if !p.as_os_str().is_empty() {
+            let _ = fs::create_dir_all(p);
+        }
+    }
+}
+
+pub fn fp_from_mind_and_version(mind_path: &str, version: u64) -> String {
+    let mind_bytes = fs::read(mind_path).unwrap_or_default();
+    let mut h = Sha256::new();
+    h.update(&mind_bytes);
+    h.update(version.to_le_bytes());
+    hex::encode(h.finalize())
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ledger de `n` entradas, uma linha por entrada (sem `\n`).
+    fn linhas(n: u64) -> Vec<String> {
+        let mut prev = GENESIS_PREV.to_string();
+        (1..=n)
+            .map(|v| {
+                let hash = format!("{:064x}", v);
+                let (line, entry) = format_entry(v, &prev, &[("hash", &hash)]).unwrap();
+                prev = entry;
+                line
+            })
+            .collect()
+    }
+
+    fn texto(linhas: &[String]) -> String {
+        linhas.iter().map(|l| format!("{}\n", l)).collect()
+    }
+
+    fn quebra(linhas: &[String]) -> LedgerError {
+        verify_ledger(&texto(linhas)).unwrap_err()
+    }
+
+    #[test]
+    fn ledger_intacto_devolve_a_cabeca() {
+        let l = linhas(4);
+        let head = verify_ledger(&texto(&l)).unwrap();
+        assert_eq!(head.version, 4);
+        assert!(l[3].ends_with(&format!("entry={}", head.entry)));
+        assert_eq!(verify_ledger("").unwrap(), LedgerHead::genesis());
+    }
+
+    #[test]
+    fn linha_editada_quebra_o_entry() {
+        let mut l = linhas(4);
+        l[2] = l[2].replace(&format!("hash={:064x}", 3), &format!("hash={:064x}", 99));
+        let e = quebra(&l);
+        assert_eq!(e.line, 3);
+        assert!(matches!(e.kind, LedgerBreak::Entry { .. }), "{}", e);
+    }
+
+    #[test]
+    fn linha_apagada_quebra_a_versao() {
+        let mut l = linhas(4);
+        l.remove(1);
+        let e = quebra(&l);
+        assert_eq!(e.line, 2);
+        assert_eq!(e.kind, LedgerBreak::Version { expected: 2, found: "3".into() });
+    }
+
+    #[test]
+    fn linhas_reordenadas_quebram_a_versao() {
+        let mut l = linhas(4);
+        l.swap(1, 2);
+        let e = quebra(&l);
+        assert_eq!(e.line, 2);
+        assert_eq!(e.kind, LedgerBreak::Version { expected: 2, found: "3".into() });
+    }
+
+    #[test]
+    fn versao_renumerada_quebra_o_prev() {
+        // apagar a linha 2 e renumerar a 3 como v=2 ainda deixa o prev errado
+        let mut l = linhas(4);
+        l.remove(1);
+        l[1] = l[1].replacen("v=3|", "v=2|", 1);
+        let e = quebra(&l);
+        assert_eq!(e.line, 2);
+        assert!(matches!(e.kind, LedgerBreak::Prev { .. }), "{}", e);
+    }
+
+    #[test]
+    fn formato_antigo_e_malformado() {
+        let e = verify_ledger("v=1|hash=abc\n").unwrap_err();
+        assert_eq!(e.line, 1);
+        assert!(matches!(e.kind, LedgerBreak::Malformed(_)));
+    }
+
+    fn arquivo(nome: &str) -> String {
+        let mut p = std::env::temp_dir();
+        p.push(format!("ledger_{}_{}.log", std::process::id(), nome));
+        let _ = fs::remove_file(&p);
+        let _ = fs::remove_file(format!("{}.torn", p.display()));
+        p.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn cauda_rasgada_vai_para_torn() {
+        let p = arquivo("rasgada");
+        let l = linhas(2);
+        fs::write(&p, format!("{}v=3|hash=ab", texto(&l))).unwrap();
+
+        let (mut w, torn) = LedgerWriter::open(&p, FsyncPolicy::Always).unwrap();
+        assert_eq!(torn, "v=3|hash=ab".len() as u64);
+        assert_eq!(w.head().version, 2);
+        assert_eq!(fs::read_to_string(format!("{}.torn", p)).unwrap(), "v=3|hash=ab\n");
+        assert_eq!(read_file(&p), texto(&l));
+
+        // o próximo append continua a cadeia, sem colar na cauda
+        w.append(&[("hash", "cd")]).unwrap();
+        assert_eq!(verify_ledger_file(&p).unwrap().version, 3);
+        let _ = fs::remove_file(&p);
+        let _ = fs::remove_file(format!("{}.torn", p));
+    }
+
+    #[test]
+    fn fsync_policy_parse() {
+        assert_eq!(FsyncPolicy::parse("always"), Ok(FsyncPolicy::Always));
+        assert_eq!(FsyncPolicy::parse(" never "), Ok(FsyncPolicy::Never));
+        assert_eq!(FsyncPolicy::parse("1"), Ok(FsyncPolicy::Always));
+        assert_eq!(FsyncPolicy::parse("64"), Ok(FsyncPolicy::Every(64)));
+        for ruim in ["0", "-1", "", "sempre", "1.5"] {
+            assert!(FsyncPolicy::parse(ruim).is_err(), "{:?}", ruim);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn appends_concorrentes_recebem_versoes_unicas() {
+        let p = arquivo("concorrente");
+        let (w, _) = LedgerWriter::open(&p, FsyncPolicy::Never).unwrap();
+        let queue = LedgerQueue::spawn(w, |head, _line| Ok(head.version));
+
+        let tarefas: Vec<_> = (0..64)
+            .map(|i| {
+                let q = queue.clone();
+                tokio::spawn(async move { q.append(vec![("hash".into(), format!("{:064x}", i))]).await })
+            })
+            .collect();
+        let mut versoes = Vec::new();
+        for t in tarefas {
+            let (head, depois) = t.await.unwrap().unwrap();
+            assert_eq!(depois, Ok(head.version));
+            versoes.push(head.version);
+        }
+        versoes.sort();
+        assert_eq!(versoes, (1..=64).collect::<Vec<u64>>());
+        assert_eq!(queue.head().version, 64);
+        assert_eq!(verify_ledger_file(&p).unwrap(), queue.head());
+        let _ = fs::remove_file(&p);
+    }
+}
@@ -0,0 +1,36 @@
+// =========================================================
+// 🌾 TERRA DOURADA CORE
+// =========================================================
+//
+// Biblioteca comum aos serviços (server, semaphore, info_local,
+// info_global) e às ferramentas de linha de comando:
+//
+//   crypto             DK-PQC-HMAC, PQC-hash → Fp, Keccak-256
+//   ledger             ledgers de texto + FP da mente
+//   info_service       writer do ledger + mente do info_local/info_global
+//   canonical          JSON canônico (RFC 8785)
+//   fxl_turbo          motor FXL (treino, mind.bin)
+//   merkle             árvore Merkle dos lotes do semaphore
+//   zk_verify          circuito td.fp.v1 e verificador Halo2
+//   vk_registry        VKs versionadas com ativação M-de-N
+//   keystore           chaves Dilithium2 cifradas em disco
+//   signature          assinatura destacada dos artefatos
+//   report_schema      schemas dos relatórios /mel
+//   media_fingerprint  fingerprint de mídia + sidecar
+//   artifact_store     Pinata / CAS local / Arweave
+//   config             terra-dourada.toml + TD_* + flags
+
+pub mod artifact_store;
+pub mod canonical;
+pub mod config;
+pub mod crypto;
+pub mod fxl_turbo;
+pub mod info_service;
+pub mod keystore;
+pub mod ledger;
+pub mod media_fingerprint;
+pub mod merkle;
+pub mod report_schema;
+pub mod signature;
+pub mod vk_registry;
+pub mod zk_verify;
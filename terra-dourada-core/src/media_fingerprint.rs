@@ -0,0 +1,121 @@
+// =========================================================
+// 📸 MEDIA FINGERPRINT — impressão digital determinística
+// =========================================================
+//
+//   media_sha256       = SHA-256(bytes da mídia), calculado em streaming
+//   location_hash      = SHA-256("td.location.v1:" || JCS(gps))
+//   sensor_fingerprint = SHA-256("td.sensors.v1:"  || JCS(sensores))
+//   fingerprint        = SHA-256("td.media.v1:"    || JCS({
+//                            media_sha256, media_size,
+//                            location_hash?, sensor_fingerprint? }))
+//
+// Os prefixos separam os domínios: um hash de GPS nunca colide com um
+// hash de sensores com o mesmo JSON. Campos ausentes ficam fora do JCS.
+//
+// O mesmo cálculo roda no POST /media e no verify_media (auditor).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::canonical::{canonicalize, CanonicalError};
+
+const READ_CHUNK: usize = 64 * 1024;
+
+/// SHA-256 + tamanho, alimentado por pedaços.
+pub struct MediaHasher {
+    sha: Sha256,
+    size: u64,
+}
+
+impl MediaHasher {
+    pub fn new() -> Self {
+        Self { sha: Sha256::new(), size: 0 }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.sha.update(chunk);
+        self.size += chunk.len() as u64;
+    }
+
+    pub fn finish(self) -> ([u8; 32], u64) {
+        (self.sha.finalize().into(), self.size)
+    }
+}
+
+impl Default for MediaHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hash de um arquivo em disco sem carregá-lo inteiro.
+pub fn hash_file(path: &Path) -> io::Result<([u8; 32], u64)> {
+    let mut f = File::open(path)?;
+    let mut hasher = MediaHasher::new();
+    let mut buf = vec![0u8; READ_CHUNK];
+    loop {
+        let n = f.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finish())
+}
+
+fn domain_hash(domain: &str, value: &Value) -> Result<String, CanonicalError> {
+    let mut sha = Sha256::new();
+    sha.update(domain.as_bytes());
+    sha.update(b":");
+    sha.update(canonicalize(value)?.as_bytes());
+    Ok(hex::encode(sha.finalize()))
+}
+
+pub fn location_hash(gps: &Value) -> Result<String, CanonicalError> {
+    domain_hash("td.location.v1", gps)
+}
+
+pub fn sensor_fingerprint(sensores: &Value) -> Result<String, CanonicalError> {
+    domain_hash("td.sensors.v1", sensores)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MediaFingerprint {
+    pub media_sha256: String,
+    pub media_size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor_fingerprint: Option<String>,
+    pub fingerprint: String,
+}
+
+impl MediaFingerprint {
+    pub fn compute(
+        media_sha256: &[u8; 32],
+        media_size: u64,
+        gps: Option<&Value>,
+        sensores: Option<&Value>,
+    ) -> Result<Self, CanonicalError> {
+        let media_sha256 = hex::encode(media_sha256);
+        let location_hash = gps.map(location_hash).transpose()?;
+        let sensor_fingerprint = sensores.map(sensor_fingerprint).transpose()?;
+
+        let mut bound = Map::new();
+        bound.insert("media_sha256".into(), json!(media_sha256));
+        bound.insert("media_size".into(), json!(media_size));
+        if let Some(h) = &location_hash {
+            bound.insert("location_hash".into(), json!(h));
+        }
+        if let Some(h) = &sensor_fingerprint {
+            bound.insert("sensor_fingerprint".into(), json!(h));
+        }
+        let fingerprint = domain_hash("td.media.v1", &Value::Object(bound))?;
+
+        Ok(Self { media_sha256, media_size, location_hash, sensor_fingerprint, fingerprint })
+    }
+}
@@ -0,0 +1,194 @@
+// =========================================================
+// 🌳 MERKLE (Keccak-256)
+// =========================================================
+//
+// Folha = keccak256(0x00 || proof_hash)
+// Nó    = keccak256(0x01 || esquerda || direita)
+//
+// O prefixo separa folhas de nós internos. Em nível ímpar, o último nó
+// sobe sem par (não é duplicado), então o caminho só tem passos reais.
+
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+fn keccak(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut out = [0u8; 32];
+    for p in parts {
+        hasher.update(p);
+    }
+    hasher.finalize(&mut out);
+    out
+}
+
+pub fn leaf_hash(proof_hash: &[u8; 32]) -> [u8; 32] {
+    keccak(&[&[LEAF_PREFIX], proof_hash])
+}
+
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    keccak(&[&[NODE_PREFIX], left, right])
+}
+
+/// Lado em que o irmão fica em relação ao nó atual.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PathStep {
+    pub sibling: String,
+    pub side: Side,
+}
+
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Constrói a árvore sobre os proof hashes na ordem dada (ordem = seq).
+    pub fn build(proof_hashes: &[[u8; 32]]) -> Option<Self> {
+        if proof_hashes.is_empty() {
+            return None;
+        }
+
+        let mut levels = vec![proof_hashes.iter().map(leaf_hash).collect::<Vec<_>>()];
+
+        while levels.last().unwrap().len() > 1 {
+            let prev = levels.last().unwrap();
+            let next = prev
+                .chunks(2)
+                .map(|pair| match pair {
+                    [l, r] => node_hash(l, r),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Some(Self { levels })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Caminho de inclusão da folha `index`, da folha até a raiz.
+    pub fn path(&self, mut index: usize) -> Vec<PathStep> {
+        let mut path = Vec::new();
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < level.len() {
+                path.push(PathStep {
+                    sibling: hex::encode(level[sibling]),
+                    side: if sibling < index { Side::Left } else { Side::Right },
+                });
+            }
+            index /= 2;
+        }
+
+        path
+    }
+}
+
+// =========================================================
+// VERIFICAÇÃO (offline, sem estado)
+// =========================================================
+
+/// Recalcula a raiz a partir do proof hash e do caminho de inclusão.
+/// Retorna `None` se algum irmão não for hex de 32 bytes.
+pub fn root_from_path(proof_hash: &[u8; 32], path: &[PathStep]) -> Option<[u8; 32]> {
+    let mut acc = leaf_hash(proof_hash);
+
+    for step in path {
+        let sibling: [u8; 32] = hex::decode(&step.sibling).ok()?.try_into().ok()?;
+        acc = match step.side {
+            Side::Left => node_hash(&sibling, &acc),
+            Side::Right => node_hash(&acc, &sibling),
+        };
+    }
+
+    Some(acc)
+}
+
+/// `true` se o caminho leva `proof_hash` exatamente até `root`.
+pub fn verify_inclusion(proof_hash: &[u8; 32], path: &[PathStep], root: &[u8; 32]) -> bool {
+    root_from_path(proof_hash, path).is_some_and(|r| &r == root)
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hashes(n: usize) -> Vec<[u8; 32]> {
+        (0..n).map(|i| keccak(&[&(i as u64).to_le_bytes()])).collect()
+    }
+
+    #[test]
+    fn caminhos_com_numero_impar_de_folhas() {
+        for n in [1, 3, 5, 7, 9] {
+            let hs = hashes(n);
+            let tree = MerkleTree::build(&hs).unwrap();
+            let root = tree.root();
+
+            for (i, h) in hs.iter().enumerate() {
+                let path = tree.path(i);
+                assert!(verify_inclusion(h, &path, &root), "n={} folha {}", n, i);
+                // nenhuma folha prova inclusão no lugar de outra
+                let outra = hs[(i + 1) % n];
+                assert_eq!(verify_inclusion(&outra, &path, &root), n == 1, "n={} folha {}", n, i);
+            }
+        }
+    }
+
+    #[test]
+    fn ultimo_no_impar_sobe_sem_par() {
+        let hs = hashes(3);
+        let tree = MerkleTree::build(&hs).unwrap();
+        let esquerda = node_hash(&leaf_hash(&hs[0]), &leaf_hash(&hs[1]));
+        assert_eq!(tree.root(), node_hash(&esquerda, &leaf_hash(&hs[2])));
+
+        // a folha 2 não tem irmão no primeiro nível: um passo só
+        let path = tree.path(2);
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0].side, Side::Left);
+        assert_eq!(path[0].sibling, hex::encode(esquerda));
+
+        assert_eq!(MerkleTree::build(&hashes(1)).unwrap().path(0).len(), 0);
+    }
+
+    #[test]
+    fn caminho_adulterado_nao_verifica() {
+        let hs = hashes(5);
+        let tree = MerkleTree::build(&hs).unwrap();
+        let root = tree.root();
+
+        let mut lado = tree.path(4);
+        lado[0].side = Side::Right;
+        assert!(!verify_inclusion(&hs[4], &lado, &root));
+
+        let mut curto = tree.path(1);
+        curto.pop();
+        assert!(!verify_inclusion(&hs[1], &curto, &root));
+
+        let mut lixo = tree.path(0);
+        lixo[0].sibling = "zz".into();
+        assert!(root_from_path(&hs[0], &lixo).is_none());
+        assert!(!verify_inclusion(&hs[0], &lixo, &root));
+    }
+}
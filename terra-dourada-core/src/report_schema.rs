@@ -0,0 +1,585 @@
+// =========================================================
+// 🧾 REPORT SCHEMA — relatórios tipados e versionados do /mel
+// =========================================================
+//
+// O corpo do POST /mel escolhe o tipo pelo campo `schema`:
+//
+//   iot_reading.v1     leitura de sensor (device/stream/métrica)
+//   field_report.v1    relatório de campo (fazenda/talhão/observação)
+//   media_evidence.v1  evidência de mídia (hash + tipo + captura)
+//   vote.v1            voto/escolha genérica (autor + candidato)
+//
+//   media_evidence.v2  mídia + GPS + sensores com fingerprint; só é
+//                      montado pelo POST /media a partir do upload
+//                      (o /mel não aceita hashes vindos do cliente)
+//
+// A validação devolve TODOS os erros de campo de uma vez (caminho,
+// código e mensagem). Campos desconhecidos são rejeitados.
+//
+// Cada schema aponta para um `ruleset_id` (política + versão das regras)
+// que entra no payload canônico e viaja com a prova.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+const ID_MAX: usize = 128;
+const TEXT_MAX: usize = 4096;
+
+/// schema → ruleset_id. Mudou a regra de validação, muda a revisão.
+pub const RULESETS: [(&str, &str); 5] = [
+    ("iot_reading.v1", "iot_reading.v1/r1"),
+    ("field_report.v1", "field_report.v1/r1"),
+    ("media_evidence.v1", "media_evidence.v1/r1"),
+    ("vote.v1", "vote.v1/r1"),
+    ("media_evidence.v2", "media_evidence.v2/r1"),
+];
+
+/// Schema que só o POST /media produz.
+pub const MEDIA_UPLOAD_SCHEMA: &str = "media_evidence.v2";
+
+// =========================================================
+// TIPOS
+// =========================================================
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "schema")]
+pub enum Report {
+    #[serde(rename = "iot_reading.v1")]
+    IotReading(IotReadingV1),
+    #[serde(rename = "field_report.v1")]
+    FieldReport(FieldReportV1),
+    #[serde(rename = "media_evidence.v1")]
+    MediaEvidence(MediaEvidenceV1),
+    #[serde(rename = "vote.v1")]
+    Vote(VoteV1),
+    #[serde(rename = "media_evidence.v2")]
+    MediaUpload(MediaEvidenceV2),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IotReadingV1 {
+    pub device_id: String,
+    pub stream_id: String,
+    pub metric: String,
+    pub value: f64,
+    pub unit: String,
+    /// unix (segundos) medido no device
+    pub measured_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub farm_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plot_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FieldReportV1 {
+    pub autor: String,
+    pub farm_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plot_id: Option<String>,
+    pub categoria: String,
+    pub observacao: String,
+    pub observed_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo: Option<Geo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Geo {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MediaEvidenceV1 {
+    pub autor: String,
+    /// SHA-256 da mídia inteira, hex minúsculo
+    pub media_sha256: String,
+    /// MIME, ex.: image/jpeg
+    pub media_type: String,
+    pub captured_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descricao: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VoteV1 {
+    pub autor: String,
+    pub candidato: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eleicao_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot_hash: Option<String>,
+}
+
+/// Montado pelo POST /media: os hashes vêm do servidor, não do cliente.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MediaEvidenceV2 {
+    pub autor: String,
+    pub media_sha256: String,
+    pub media_size: u64,
+    pub media_type: String,
+    /// CID/id do arquivo no artifact store de mídia
+    pub media_cid: String,
+    pub captured_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensor_fingerprint: Option<String>,
+    /// ver media_fingerprint.rs
+    pub fingerprint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descricao: Option<String>,
+}
+
+/// Parte `sidecar` do POST /media (JSON).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MediaSidecar {
+    pub autor: String,
+    pub captured_at: i64,
+    /// se ausente, vale o Content-Type da parte `media`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descricao: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gps: Option<Gps>,
+    /// leituras livres dos sensores no momento da captura
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensores: Option<Map<String, Value>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Gps {
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accuracy_m: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt_m: Option<f64>,
+}
+
+impl Report {
+    pub fn schema(&self) -> &'static str {
+        match self {
+            Report::IotReading(_) => "iot_reading.v1",
+            Report::FieldReport(_) => "field_report.v1",
+            Report::MediaEvidence(_) => "media_evidence.v1",
+            Report::Vote(_) => "vote.v1",
+            Report::MediaUpload(_) => MEDIA_UPLOAD_SCHEMA,
+        }
+    }
+
+    pub fn ruleset_id(&self) -> &'static str {
+        ruleset_for(self.schema()).expect("todo schema tem ruleset")
+    }
+
+    /// Quem produziu o relatório (device, no caso de IoT).
+    pub fn autor(&self) -> &str {
+        match self {
+            Report::IotReading(r) => &r.device_id,
+            Report::FieldReport(r) => &r.autor,
+            Report::MediaEvidence(r) => &r.autor,
+            Report::Vote(r) => &r.autor,
+            Report::MediaUpload(r) => &r.autor,
+        }
+    }
+
+    pub fn candidato(&self) -> Option<&str> {
+        match self {
+            Report::Vote(r) => Some(&r.candidato),
+            _ => None,
+        }
+    }
+
+    /// Objeto que entra no payload canônico: o relatório + `ruleset_id`.
+    pub fn claim(&self) -> Value {
+        let mut v = serde_json::to_value(self).expect("Report serializável");
+        if let Value::Object(obj) = &mut v {
+            obj.insert("ruleset_id".into(), Value::String(self.ruleset_id().into()));
+        }
+        v
+    }
+}
+
+pub fn ruleset_for(schema: &str) -> Option<&'static str> {
+    RULESETS.iter().find(|(s, _)| *s == schema).map(|(_, r)| *r)
+}
+
+// =========================================================
+// ERROS DE CAMPO
+// =========================================================
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FieldError {
+    /// caminho do campo, ex.: "geo.lat"
+    pub field: String,
+    /// required | type | empty | too_long | format | range | unknown_field | unknown_schema
+    pub code: &'static str,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SchemaErrors {
+    pub schema: Option<String>,
+    pub errors: Vec<FieldError>,
+}
+
+// =========================================================
+// VALIDAÇÃO
+// =========================================================
+
+/// Valida o corpo do /mel e devolve o relatório tipado.
+pub fn parse_report(value: &Value) -> Result<Report, SchemaErrors> {
+    let Some(obj) = value.as_object() else {
+        return Err(SchemaErrors {
+            schema: None,
+            errors: vec![FieldError {
+                field: "".into(),
+                code: "type",
+                message: "o corpo deve ser um objeto JSON".into(),
+            }],
+        });
+    };
+
+    let schema = match obj.get("schema") {
+        Some(Value::String(s)) => s.clone(),
+        Some(_) => return Err(single(None, "schema", "type", "deve ser string")),
+        None => {
+            return Err(single(
+                None,
+                "schema",
+                "required",
+                &format!("obrigatório; um de: {}", schema_list()),
+            ))
+        }
+    };
+
+    let mut f = Fields::new(obj, "");
+    f.seen.push("schema".into());
+
+    let report = match schema.as_str() {
+        "iot_reading.v1" => Report::IotReading(IotReadingV1 {
+            device_id: f.req_str("device_id", ID_MAX),
+            stream_id: f.req_str("stream_id", ID_MAX),
+            metric: f.req_str("metric", ID_MAX),
+            value: f.req_number("value"),
+            unit: f.req_str("unit", ID_MAX),
+            measured_at: f.req_timestamp("measured_at"),
+            farm_id: f.opt_str("farm_id", ID_MAX),
+            plot_id: f.opt_str("plot_id", ID_MAX),
+        }),
+        "field_report.v1" => Report::FieldReport(FieldReportV1 {
+            autor: f.req_str("autor", ID_MAX),
+            farm_id: f.req_str("farm_id", ID_MAX),
+            plot_id: f.opt_str("plot_id", ID_MAX),
+            categoria: f.req_str("categoria", ID_MAX),
+            observacao: f.req_str("observacao", TEXT_MAX),
+            observed_at: f.req_timestamp("observed_at"),
+            geo: f.opt_geo("geo"),
+        }),
+        "media_evidence.v1" => Report::MediaEvidence(MediaEvidenceV1 {
+            autor: f.req_str("autor", ID_MAX),
+            media_sha256: f.req_hex32("media_sha256"),
+            media_type: f.req_mime("media_type"),
+            captured_at: f.req_timestamp("captured_at"),
+            descricao: f.opt_str("descricao", TEXT_MAX),
+        }),
+        "vote.v1" => Report::Vote(VoteV1 {
+            autor: f.req_str("autor", ID_MAX),
+            candidato: f.req_str("candidato", ID_MAX),
+            eleicao_id: f.opt_str("eleicao_id", ID_MAX),
+            screenshot_hash: f.opt_hex32("screenshot_hash"),
+        }),
+        MEDIA_UPLOAD_SCHEMA => {
+            return Err(single(
+                Some(schema.clone()),
+                "schema",
+                "unknown_schema",
+                "media_evidence.v2 só é aceito pelo POST /media (multipart)",
+            ))
+        }
+        other => {
+            return Err(single(
+                Some(other.to_string()),
+                "schema",
+                "unknown_schema",
+                &format!("schema desconhecido; um de: {}", schema_list()),
+            ))
+        }
+    };
+
+    let errors = f.finish();
+    if errors.is_empty() {
+        Ok(report)
+    } else {
+        Err(SchemaErrors { schema: Some(schema), errors })
+    }
+}
+
+/// Valida a parte `sidecar` do POST /media.
+pub fn parse_media_sidecar(value: &Value) -> Result<MediaSidecar, SchemaErrors> {
+    let Some(obj) = value.as_object() else {
+        return Err(single(None, "sidecar", "type", "o sidecar deve ser um objeto JSON"));
+    };
+
+    let mut f = Fields::new(obj, "sidecar.");
+    let sidecar = MediaSidecar {
+        autor: f.req_str("autor", ID_MAX),
+        captured_at: f.req_timestamp("captured_at"),
+        media_type: f.opt_mime("media_type"),
+        descricao: f.opt_str("descricao", TEXT_MAX),
+        gps: f.opt_gps("gps"),
+        sensores: f.opt_object("sensores"),
+    };
+
+    let errors = f.finish();
+    if errors.is_empty() {
+        Ok(sidecar)
+    } else {
+        Err(SchemaErrors { schema: Some(MEDIA_UPLOAD_SCHEMA.into()), errors })
+    }
+}
+
+fn schema_list() -> String {
+    RULESETS
+        .iter()
+        .map(|(s, _)| *s)
+        .filter(|s| *s != MEDIA_UPLOAD_SCHEMA)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn single(schema: Option<String>, field: &str, code: &'static str, message: &str) -> SchemaErrors {
+    SchemaErrors {
+        schema,
+        errors: vec![FieldError { field: field.into(), code, message: message.into() }],
+    }
+}
+
+/// Lê campos de um objeto acumulando erros. Campo obrigatório inválido
+/// devolve um valor vazio — o relatório só é usado se não houver erros.
+struct Fields<'a> {
+    obj: &'a Map<String, Value>,
+    prefix: &'a str,
+    seen: Vec<String>,
+    errors: Vec<FieldError>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(obj: &'a Map<String, Value>, prefix: &'a str) -> Self {
+        Self { obj, prefix, seen: Vec::new(), errors: Vec::new() }
+    }
+
+    fn path(&self, name: &str) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+
+    fn error(&mut self, name: &str, code: &'static str, message: impl Into<String>) {
+        let field = self.path(name);
+        self.errors.push(FieldError { field, code, message: message.into() });
+    }
+
+    /// Valor presente e não-null; registra `required` se obrigatório.
+    fn take(&mut self, name: &str, required: bool) -> Option<&'a Value> {
+        self.seen.push(name.into());
+        let obj = self.obj;
+        match obj.get(name) {
+            Some(Value::Null) | None => {
+                if required {
+                    self.error(name, "required", "campo obrigatório");
+                }
+                None
+            }
+            Some(v) => Some(v),
+        }
+    }
+
+    fn str_field(&mut self, name: &str, required: bool, max: usize) -> Option<String> {
+        let v = self.take(name, required)?;
+        let Some(s) = v.as_str() else {
+            self.error(name, "type", "deve ser string");
+            return None;
+        };
+        if s.trim().is_empty() {
+            self.error(name, "empty", "não pode ser vazio");
+            return None;
+        }
+        if s.chars().count() > max {
+            self.error(name, "too_long", format!("máximo de {} caracteres", max));
+            return None;
+        }
+        Some(s.to_string())
+    }
+
+    fn req_str(&mut self, name: &str, max: usize) -> String {
+        self.str_field(name, true, max).unwrap_or_default()
+    }
+
+    fn opt_str(&mut self, name: &str, max: usize) -> Option<String> {
+        self.str_field(name, false, max)
+    }
+
+    fn hex32_field(&mut self, name: &str, required: bool) -> Option<String> {
+        let s = self.str_field(name, required, 64)?;
+        if s.len() != 64 || !s.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+            self.error(name, "format", "deve ser SHA-256 em hex minúsculo (64 caracteres)");
+            return None;
+        }
+        Some(s)
+    }
+
+    fn req_hex32(&mut self, name: &str) -> String {
+        self.hex32_field(name, true).unwrap_or_default()
+    }
+
+    fn opt_hex32(&mut self, name: &str) -> Option<String> {
+        self.hex32_field(name, false)
+    }
+
+    fn mime_field(&mut self, name: &str, required: bool) -> Option<String> {
+        let s = self.str_field(name, required, ID_MAX)?;
+        if is_mime(&s) {
+            Some(s)
+        } else {
+            self.error(name, "format", "deve ser um MIME type, ex.: image/jpeg");
+            None
+        }
+    }
+
+    fn req_mime(&mut self, name: &str) -> String {
+        self.mime_field(name, true).unwrap_or_default()
+    }
+
+    fn opt_mime(&mut self, name: &str) -> Option<String> {
+        self.mime_field(name, false)
+    }
+
+    fn opt_object(&mut self, name: &str) -> Option<Map<String, Value>> {
+        let v = self.take(name, false)?;
+        match v.as_object() {
+            Some(obj) => Some(obj.clone()),
+            None => {
+                self.error(name, "type", "deve ser objeto");
+                None
+            }
+        }
+    }
+
+    fn number_field(&mut self, name: &str, required: bool) -> Option<f64> {
+        let v = self.take(name, required)?;
+        match v.as_f64() {
+            Some(n) => Some(n),
+            None => {
+                self.error(name, "type", "deve ser número");
+                None
+            }
+        }
+    }
+
+    fn req_number(&mut self, name: &str) -> f64 {
+        self.number_field(name, true).unwrap_or_default()
+    }
+
+    /// Unix em segundos: inteiro ≥ 0.
+    fn req_timestamp(&mut self, name: &str) -> i64 {
+        let Some(v) = self.take(name, true) else {
+            return 0;
+        };
+        match v.as_i64() {
+            Some(t) if t >= 0 => t,
+            Some(_) => {
+                self.error(name, "range", "deve ser ≥ 0");
+                0
+            }
+            None => {
+                self.error(name, "type", "deve ser inteiro (unix em segundos)");
+                0
+            }
+        }
+    }
+
+    fn opt_geo(&mut self, name: &str) -> Option<Geo> {
+        let v = self.take(name, false)?;
+        let Some(obj) = v.as_object() else {
+            self.error(name, "type", "deve ser objeto {lat, lon}");
+            return None;
+        };
+
+        let prefix = format!("{}.", self.path(name));
+        let mut sub = Fields::new(obj, &prefix);
+        let (lat, lon) = sub.lat_lon();
+        let errors = sub.finish();
+
+        if errors.is_empty() {
+            Some(Geo { lat, lon })
+        } else {
+            self.errors.extend(errors);
+            None
+        }
+    }
+
+    fn opt_gps(&mut self, name: &str) -> Option<Gps> {
+        let v = self.take(name, false)?;
+        let Some(obj) = v.as_object() else {
+            self.error(name, "type", "deve ser objeto {lat, lon, accuracy_m?, alt_m?}");
+            return None;
+        };
+
+        let prefix = format!("{}.", self.path(name));
+        let mut sub = Fields::new(obj, &prefix);
+        let (lat, lon) = sub.lat_lon();
+        let accuracy_m = sub.number_field("accuracy_m", false);
+        if accuracy_m.is_some_and(|a| a < 0.0) {
+            sub.error("accuracy_m", "range", "deve ser ≥ 0");
+        }
+        let alt_m = sub.number_field("alt_m", false);
+        let errors = sub.finish();
+
+        if errors.is_empty() {
+            Some(Gps { lat, lon, accuracy_m, alt_m })
+        } else {
+            self.errors.extend(errors);
+            None
+        }
+    }
+
+    /// `lat` / `lon` obrigatórios, com faixa.
+    fn lat_lon(&mut self) -> (f64, f64) {
+        let lat = self.number_field("lat", true);
+        let lon = self.number_field("lon", true);
+        if lat.is_some_and(|l| !(-90.0..=90.0).contains(&l)) {
+            self.error("lat", "range", "deve estar entre -90 e 90");
+        }
+        if lon.is_some_and(|l| !(-180.0..=180.0).contains(&l)) {
+            self.error("lon", "range", "deve estar entre -180 e 180");
+        }
+        (lat.unwrap_or_default(), lon.unwrap_or_default())
+    }
+
+    /// Fecha a leitura: acusa campos que nenhum validador consumiu.
+    fn finish(mut self) -> Vec<FieldError> {
+        let obj = self.obj;
+        let mut unknown: Vec<&String> = obj
+            .keys()
+            .filter(|k| !self.seen.iter().any(|s| s == *k))
+            .collect();
+        unknown.sort();
+        for k in unknown {
+            let field = self.path(k);
+            self.errors.push(FieldError {
+                field,
+                code: "unknown_field",
+                message: "campo não previsto no schema".into(),
+            });
+        }
+        self.errors
+    }
+}
+
+pub fn is_mime(s: &str) -> bool {
+    match s.split_once('/') {
+        Some((t, sub)) => !t.is_empty() && !sub.is_empty() && !s.contains(char::is_whitespace),
+        None => false,
+    }
+}
@@ -0,0 +1,202 @@
+// =========================================================
+// ✍️ ASSINATURA DESTACADA — Dilithium2 verificável por terceiros
+// =========================================================
+//
+//   mensagem   = "td.sig.v1:" || payload
+//   payload    = JCS(claim) ":" timestamp      (ver "Canonical JSON.md")
+//   assinatura = Dilithium2.Sign(sk[key_id], mensagem)
+//
+// O prefixo separa esta assinatura publicada das assinaturas internas
+// do DK-PQC-HMAC e do PQC-HASH → Fp, que continuam nunca expostas.
+// Quem tem a chave pública (GET /keys) confere a origem offline;
+// `verify_published` também exige o timestamp dentro da validade da chave.
+
+use base64::engine::{general_purpose, Engine as _};
+use crystals_dilithium::dilithium2::{Keypair, PublicKey, PUBLICKEYBYTES, SIGNBYTES};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const SIGNATURE_DOMAIN: &str = "td.sig.v1";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DetachedSignature {
+    pub algorithm: String,
+    pub key_id: String,
+    /// Base64 (padrão) dos SIGNBYTES da assinatura.
+    pub value: String,
+}
+
+/// Chave como publicada em GET /keys.
+#[derive(Deserialize, Clone, Debug)]
+pub struct PublishedKey {
+    pub key_id: String,
+    pub algorithm: String,
+    pub public_key: String,
+    pub valid_from: u64,
+    pub valid_until: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    PublicKeyLength(usize),
+    SignatureLength(usize),
+    Encoding(String),
+    /// key_id ou algoritmo da assinatura ≠ chave publicada
+    KeyMismatch(String),
+    /// timestamp fora de [valid_from, valid_until)
+    OutsideValidity { timestamp: u64, valid_from: u64, valid_until: u64 },
+    Invalid,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::PublicKeyLength(n) => {
+                write!(f, "chave pública com {} bytes (esperado {})", n, PUBLICKEYBYTES)
+            }
+            SignatureError::SignatureLength(n) => {
+                write!(f, "assinatura com {} bytes (esperado {})", n, SIGNBYTES)
+            }
+            SignatureError::Encoding(e) => write!(f, "base64 inválido: {}", e),
+            SignatureError::KeyMismatch(e) => write!(f, "chave não confere: {}", e),
+            SignatureError::OutsideValidity { timestamp, valid_from, valid_until } => write!(
+                f,
+                "timestamp {} fora da validade da chave [{}, {})",
+                timestamp, valid_from, valid_until
+            ),
+            SignatureError::Invalid => write!(f, "assinatura não confere"),
+        }
+    }
+}
+
+fn signed_message(payload: &[u8]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(SIGNATURE_DOMAIN.len() + 1 + payload.len());
+    msg.extend_from_slice(SIGNATURE_DOMAIN.as_bytes());
+    msg.push(b':');
+    msg.extend_from_slice(payload);
+    msg
+}
+
+pub fn sign_payload(keypair: &Keypair, key_id: &str, payload: &[u8]) -> DetachedSignature {
+    let sig = keypair.sign(&signed_message(payload));
+    DetachedSignature {
+        algorithm: "dilithium2".into(),
+        key_id: key_id.into(),
+        value: general_purpose::STANDARD.encode(sig),
+    }
+}
+
+/// Confere a assinatura destacada de `payload` com a chave pública crua.
+pub fn verify_signature(public_key: &[u8], payload: &[u8], signature: &[u8]) -> Result<(), SignatureError> {
+    if public_key.len() != PUBLICKEYBYTES {
+        return Err(SignatureError::PublicKeyLength(public_key.len()));
+    }
+    if signature.len() != SIGNBYTES {
+        return Err(SignatureError::SignatureLength(signature.len()));
+    }
+    if PublicKey::from_bytes(public_key).verify(&signed_message(payload), signature) {
+        Ok(())
+    } else {
+        Err(SignatureError::Invalid)
+    }
+}
+
+/// Mesma conferência, com chave e assinatura em base64 (como publicadas).
+pub fn verify_signature_b64(public_key_b64: &str, payload: &[u8], signature_b64: &str) -> Result<(), SignatureError> {
+    let pk = general_purpose::STANDARD
+        .decode(public_key_b64)
+        .map_err(|e| SignatureError::Encoding(e.to_string()))?;
+    let sig = general_purpose::STANDARD
+        .decode(signature_b64)
+        .map_err(|e| SignatureError::Encoding(e.to_string()))?;
+    verify_signature(&pk, payload, &sig)
+}
+
+/// Confere `sig` contra a chave publicada: mesmo key_id e algoritmo,
+/// `timestamp` dentro da validade da chave, e a assinatura em si.
+pub fn verify_published(
+    key: &PublishedKey,
+    payload: &[u8],
+    timestamp: u64,
+    sig: &DetachedSignature,
+) -> Result<(), SignatureError> {
+    if sig.key_id != key.key_id {
+        return Err(SignatureError::KeyMismatch(format!("key_id {} ≠ {}", sig.key_id, key.key_id)));
+    }
+    if sig.algorithm != key.algorithm {
+        return Err(SignatureError::KeyMismatch(format!("algoritmo {} ≠ {}", sig.algorithm, key.algorithm)));
+    }
+    if timestamp < key.valid_from || timestamp >= key.valid_until {
+        return Err(SignatureError::OutsideValidity {
+            timestamp,
+            valid_from: key.valid_from,
+            valid_until: key.valid_until,
+        });
+    }
+    verify_signature_b64(&key.public_key, payload, &sig.value)
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical;
+    use serde_json::json;
+
+    fn payload(claim: &serde_json::Value, timestamp: u64) -> String {
+        format!("{}:{}", canonical::canonicalize(claim).unwrap(), timestamp)
+    }
+
+    fn assinado(timestamp: u64) -> (PublishedKey, serde_json::Value, DetachedSignature) {
+        let keypair = Keypair::generate(None);
+        let key = PublishedKey {
+            key_id: "dil2-teste".into(),
+            algorithm: "dilithium2".into(),
+            public_key: general_purpose::STANDARD.encode(keypair.public.to_bytes()),
+            valid_from: 1_000,
+            valid_until: 2_000,
+        };
+        let claim = json!({ "schema": "vote.v1", "autor": "ana", "candidato": "mel", "ruleset_id": "vote.v1/r1" });
+        let sig = sign_payload(&keypair, &key.key_id, payload(&claim, timestamp).as_bytes());
+        (key, claim, sig)
+    }
+
+    #[test]
+    fn claim_adulterado_nao_confere() {
+        let (key, mut claim, sig) = assinado(1_500);
+        assert_eq!(verify_published(&key, payload(&claim, 1_500).as_bytes(), 1_500, &sig), Ok(()));
+
+        claim["candidato"] = json!("cera");
+        assert_eq!(
+            verify_published(&key, payload(&claim, 1_500).as_bytes(), 1_500, &sig),
+            Err(SignatureError::Invalid)
+        );
+
+        // timestamp também faz parte do payload
+        let (key, claim, sig) = assinado(1_500);
+        assert_eq!(
+            verify_published(&key, payload(&claim, 1_501).as_bytes(), 1_501, &sig),
+            Err(SignatureError::Invalid)
+        );
+    }
+
+    #[test]
+    fn chave_fora_da_validade_nao_confere() {
+        for ts in [999, 2_000] {
+            let (key, claim, sig) = assinado(ts);
+            let err = verify_published(&key, payload(&claim, ts).as_bytes(), ts, &sig).unwrap_err();
+            assert!(matches!(err, SignatureError::OutsideValidity { timestamp, .. } if timestamp == ts), "{}", err);
+        }
+
+        let (mut key, claim, sig) = assinado(1_000);
+        assert_eq!(verify_published(&key, payload(&claim, 1_000).as_bytes(), 1_000, &sig), Ok(()));
+        key.key_id = "dil2-outra".into();
+        assert!(matches!(
+            verify_published(&key, payload(&claim, 1_000).as_bytes(), 1_000, &sig),
+            Err(SignatureError::KeyMismatch(_))
+        ));
+    }
+}
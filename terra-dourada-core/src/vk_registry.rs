@@ -0,0 +1,413 @@
+// =========================================================
+// 🏛️ VK REGISTRY — verifying keys por fingerprint, com governança
+// =========================================================
+//
+// fingerprint = hex(SHA-256(vk_bytes))  ("fingerprint circuit.md" §1.2)
+//
+// Ciclo de vida de cada versão:
+//
+//   proposed ──(M de N aprovações assinadas)──► active ──(próxima ativa)──► retired
+//
+// /register_vk_id só PROPÕE. A ativação exige `threshold` aprovações
+// distintas dos aprovadores de TD_VK_APPROVERS, cada uma uma assinatura
+// Dilithium2 sobre:
+//
+//   "td.vk.activate.v2:" || tenant_id || ":" || fingerprint || ":" || version
+//
+// A versão entra na mensagem: reativar uma VK aposentada é uma nova
+// proposta, com novas aprovações. O tenant também: os aprovadores são os
+// mesmos para todos os tenants, e uma aprovação não pode ser reaproveitada
+// no registro de outro. Nada é apagado; o arquivo guarda o
+// histórico inteiro (tmp + rename a cada mudança).
+
+use base64::engine::{general_purpose, Engine as _};
+use crystals_dilithium::dilithium2::{PublicKey, PUBLICKEYBYTES, SIGNBYTES};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::zk_verify::vk_fingerprint;
+
+pub const APPROVAL_DOMAIN: &str = "td.vk.activate.v2";
+
+pub fn approval_message(tenant_id: &str, fingerprint: &str, version: u32) -> Vec<u8> {
+    format!("{}:{}:{}:{}", APPROVAL_DOMAIN, tenant_id, fingerprint, version).into_bytes()
+}
+
+// =========================================================
+// GOVERNANÇA (M de N)
+// =========================================================
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Approver {
+    pub id: String,
+    pub role: String,
+    /// Chave pública Dilithium2, base64.
+    pub public_key: String,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Governance {
+    pub threshold: usize,
+    pub approvers: Vec<Approver>,
+}
+
+impl Governance {
+    /// Lê o arquivo de aprovadores. Ausente → nenhuma VK pode ser ativada.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self { threshold: 1, approvers: Vec::new() });
+        }
+        let txt = fs::read_to_string(path).map_err(|e| format!("falha ao ler {:?}: {}", path, e))?;
+        let gov: Governance = serde_json::from_str(&txt).map_err(|e| format!("{:?} inválido: {}", path, e))?;
+
+        if gov.threshold == 0 || gov.threshold > gov.approvers.len() {
+            return Err(format!(
+                "threshold {} inválido para {} aprovadores",
+                gov.threshold,
+                gov.approvers.len()
+            ));
+        }
+        for (i, a) in gov.approvers.iter().enumerate() {
+            let pk = general_purpose::STANDARD
+                .decode(&a.public_key)
+                .map_err(|e| format!("aprovador {}: chave inválida: {}", a.id, e))?;
+            if pk.len() != PUBLICKEYBYTES {
+                return Err(format!("aprovador {}: chave com {} bytes", a.id, pk.len()));
+            }
+            if gov.approvers[..i].iter().any(|b| b.id == a.id || b.public_key == a.public_key) {
+                return Err(format!("aprovador {} repetido", a.id));
+            }
+        }
+        Ok(gov)
+    }
+
+    fn approver(&self, id: &str) -> Option<&Approver> {
+        self.approvers.iter().find(|a| a.id == id)
+    }
+}
+
+// =========================================================
+// REGISTRO
+// =========================================================
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum VkStatus {
+    Proposed,
+    Active,
+    Retired,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Approval {
+    pub approver_id: String,
+    pub role: String,
+    pub signature: String,
+    pub approved_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VkEntry {
+    pub fingerprint: String,
+    pub version: u32,
+    pub circuit: String,
+    pub k: u32,
+    pub vk_b64: String,
+    pub status: VkStatus,
+    pub proposed_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activated_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retired_at: Option<u64>,
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct RegistryFile {
+    entries: Vec<VkEntry>,
+}
+
+#[derive(Debug)]
+pub enum RegistryError {
+    NotFound(String),
+    VersionMismatch { expected: u32, got: u32 },
+    NotProposed(VkStatus),
+    UnknownApprover(String),
+    AlreadyApproved(String),
+    BadSignature(String),
+    Io(String),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::NotFound(fp) => write!(f, "VK {} não registrada", fp),
+            RegistryError::VersionMismatch { expected, got } => {
+                write!(f, "versão {} não é a proposta atual ({})", got, expected)
+            }
+            RegistryError::NotProposed(s) => write!(f, "VK não está em proposta (status {:?})", s),
+            RegistryError::UnknownApprover(id) => write!(f, "aprovador {} desconhecido", id),
+            RegistryError::AlreadyApproved(id) => write!(f, "aprovador {} já aprovou", id),
+            RegistryError::BadSignature(id) => write!(f, "assinatura de {} não confere", id),
+            RegistryError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+pub struct VkRegistry {
+    path: PathBuf,
+    tenant_id: String,
+    file: RegistryFile,
+    gov: Governance,
+}
+
+impl VkRegistry {
+    pub fn open(path: &str, tenant_id: &str, gov: Governance) -> Result<Self, String> {
+        let path = PathBuf::from(path);
+        if let Some(p) = path.parent() {
+            if !p.as_os_str().is_empty() {
+                fs::create_dir_all(p).map_err(|e| format!("falha create_dir_all({:?}): {}", p, e))?;
+            }
+        }
+
+        let file = if path.exists() {
+            let txt = fs::read_to_string(&path).map_err(|e| format!("falha ao ler {:?}: {}", path, e))?;
+            serde_json::from_str(&txt).map_err(|e| format!("registro de VK corrompido {:?}: {}", path, e))?
+        } else {
+            RegistryFile::default()
+        };
+
+        Ok(Self { path, tenant_id: tenant_id.into(), file, gov })
+    }
+
+    fn persist(&self) -> Result<(), String> {
+        let tmp = self.path.with_extension("json.tmp");
+        let bytes = serde_json::to_vec_pretty(&self.file)
+            .map_err(|e| format!("falha serializando registro de VK: {}", e))?;
+        let mut f = File::create(&tmp).map_err(|e| format!("falha ao criar {:?}: {}", tmp, e))?;
+        f.write_all(&bytes).map_err(|e| format!("falha ao escrever registro de VK: {}", e))?;
+        f.sync_all().map_err(|e| format!("falha no fsync: {}", e))?;
+        fs::rename(&tmp, &self.path).map_err(|e| format!("falha ao renomear registro de VK: {}", e))
+    }
+
+    pub fn governance(&self) -> &Governance {
+        &self.gov
+    }
+
+    pub fn entries(&self) -> &[VkEntry] {
+        &self.file.entries
+    }
+
+    /// Versão mais recente de um fingerprint.
+    pub fn get(&self, fingerprint: &str) -> Option<&VkEntry> {
+        self.file.entries.iter().rev().find(|e| e.fingerprint == fingerprint)
+    }
+
+    pub fn active(&self) -> Option<&VkEntry> {
+        self.file.entries.iter().find(|e| e.status == VkStatus::Active)
+    }
+
+    /// Registra a VK como proposta. Se já está proposta ou ativa, devolve
+    /// a entrada existente; se foi aposentada, abre nova versão.
+    pub fn propose(&mut self, vk_bytes: &[u8], circuit: &str, k: u32, now: u64) -> Result<VkEntry, String> {
+        let fingerprint = vk_fingerprint(vk_bytes);
+
+        if let Some(e) = self.get(&fingerprint) {
+            if e.status != VkStatus::Retired {
+                return Ok(e.clone());
+            }
+        }
+
+        let version = self.file.entries.iter().map(|e| e.version).max().unwrap_or(0) + 1;
+        let entry = VkEntry {
+            fingerprint,
+            version,
+            circuit: circuit.into(),
+            k,
+            vk_b64: general_purpose::STANDARD.encode(vk_bytes),
+            status: VkStatus::Proposed,
+            proposed_at: now,
+            activated_at: None,
+            retired_at: None,
+            approvals: Vec::new(),
+        };
+
+        self.file.entries.push(entry.clone());
+        if let Err(e) = self.persist() {
+            self.file.entries.pop();
+            return Err(e);
+        }
+        Ok(entry)
+    }
+
+    /// Registra uma aprovação; ao atingir o threshold, ativa a VK e
+    /// aposenta a anterior. Devolve a entrada atualizada.
+    pub fn approve(
+        &mut self,
+        fingerprint: &str,
+        version: u32,
+        approver_id: &str,
+        signature_b64: &str,
+        now: u64,
+    ) -> Result<VkEntry, RegistryError> {
+        let idx = self
+            .file
+            .entries
+            .iter()
+            .rposition(|e| e.fingerprint == fingerprint)
+            .ok_or_else(|| RegistryError::NotFound(fingerprint.into()))?;
+
+        let entry = &self.file.entries[idx];
+        if entry.version != version {
+            return Err(RegistryError::VersionMismatch { expected: entry.version, got: version });
+        }
+        if entry.status != VkStatus::Proposed {
+            return Err(RegistryError::NotProposed(entry.status));
+        }
+        if entry.approvals.iter().any(|a| a.approver_id == approver_id) {
+            return Err(RegistryError::AlreadyApproved(approver_id.into()));
+        }
+
+        let approver = self
+            .gov
+            .approver(approver_id)
+            .ok_or_else(|| RegistryError::UnknownApprover(approver_id.into()))?
+            .clone();
+
+        let pk = general_purpose::STANDARD
+            .decode(&approver.public_key)
+            .map_err(|_| RegistryError::UnknownApprover(approver_id.into()))?;
+        let sig = general_purpose::STANDARD
+            .decode(signature_b64)
+            .map_err(|_| RegistryError::BadSignature(approver_id.into()))?;
+        if sig.len() != SIGNBYTES
+            || !PublicKey::from_bytes(&pk).verify(&approval_message(&self.tenant_id, fingerprint, version), &sig)
+        {
+            return Err(RegistryError::BadSignature(approver_id.into()));
+        }
+
+        let before = std::mem::take(&mut self.file.entries);
+        let mut entries = before.clone();
+
+        entries[idx].approvals.push(Approval {
+            approver_id: approver.id.clone(),
+            role: approver.role.clone(),
+            signature: signature_b64.into(),
+            approved_at: now,
+        });
+
+        if entries[idx].approvals.len() >= self.gov.threshold {
+            for e in entries.iter_mut().filter(|e| e.status == VkStatus::Active) {
+                e.status = VkStatus::Retired;
+                e.retired_at = Some(now);
+            }
+            entries[idx].status = VkStatus::Active;
+            entries[idx].activated_at = Some(now);
+        }
+
+        self.file.entries = entries;
+        if let Err(e) = self.persist() {
+            self.file.entries = before;
+            return Err(RegistryError::Io(e));
+        }
+        Ok(self.file.entries[idx].clone())
+    }
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crystals_dilithium::dilithium2::Keypair;
+
+    fn arquivo(nome: &str) -> String {
+        let mut p = std::env::temp_dir();
+        p.push(format!("vk_registry_{}_{}.json", std::process::id(), nome));
+        let _ = fs::remove_file(&p);
+        p.to_string_lossy().into_owned()
+    }
+
+    /// 2 de 3 aprovadores.
+    fn governanca() -> (Governance, Vec<Keypair>) {
+        let pares: Vec<Keypair> = (0..3).map(|_| Keypair::generate(None)).collect();
+        let approvers = pares
+            .iter()
+            .enumerate()
+            .map(|(i, kp)| Approver {
+                id: format!("a{}", i),
+                role: "operador".into(),
+                public_key: general_purpose::STANDARD.encode(kp.public.to_bytes()),
+            })
+            .collect();
+        (Governance { threshold: 2, approvers }, pares)
+    }
+
+    fn assinar(kp: &Keypair, tenant: &str, fp: &str, version: u32) -> String {
+        general_purpose::STANDARD.encode(kp.sign(&approval_message(tenant, fp, version)))
+    }
+
+    #[test]
+    fn ativa_com_m_de_n_aprovadores_distintos() {
+        let p = arquivo("m_de_n");
+        let (gov, pares) = governanca();
+        let mut reg = VkRegistry::open(&p, "t1", gov.clone()).unwrap();
+        let e = reg.propose(b"vk-a", "td.fp.v1", 8, 100).unwrap();
+        let (fp, v) = (e.fingerprint.clone(), e.version);
+
+        let e = reg.approve(&fp, v, "a0", &assinar(&pares[0], "t1", &fp, v), 101).unwrap();
+        assert_eq!(e.status, VkStatus::Proposed);
+
+        // o mesmo aprovador não conta duas vezes
+        let err = reg.approve(&fp, v, "a0", &assinar(&pares[0], "t1", &fp, v), 102).err().unwrap();
+        assert!(matches!(err, RegistryError::AlreadyApproved(id) if id == "a0"));
+
+        // quem não está na governança não aprova, nem com assinatura válida
+        let intruso = Keypair::generate(None);
+        let err = reg.approve(&fp, v, "intruso", &assinar(&intruso, "t1", &fp, v), 102).err().unwrap();
+        assert!(matches!(err, RegistryError::UnknownApprover(_)));
+
+        // chave de um membro assinando como outro
+        let err = reg.approve(&fp, v, "a1", &assinar(&pares[2], "t1", &fp, v), 102).err().unwrap();
+        assert!(matches!(err, RegistryError::BadSignature(_)));
+
+        // aprovação de outro tenant não vale aqui
+        let err = reg.approve(&fp, v, "a1", &assinar(&pares[1], "t2", &fp, v), 102).err().unwrap();
+        assert!(matches!(err, RegistryError::BadSignature(_)));
+        assert_eq!(reg.get(&fp).unwrap().approvals.len(), 1);
+
+        let e = reg.approve(&fp, v, "a1", &assinar(&pares[1], "t1", &fp, v), 103).unwrap();
+        assert_eq!(e.status, VkStatus::Active);
+        assert_eq!(e.activated_at, Some(103));
+
+        // ativa a próxima: a anterior aposenta, e o estado sobrevive ao reopen
+        let b = reg.propose(b"vk-b", "td.fp.v1", 8, 200).unwrap();
+        for (i, kp) in pares.iter().enumerate().skip(1) {
+            reg.approve(&b.fingerprint, b.version, &format!("a{}", i), &assinar(kp, "t1", &b.fingerprint, b.version), 201)
+                .unwrap();
+        }
+        let reg = VkRegistry::open(&p, "t1", gov).unwrap();
+        assert_eq!(reg.active().unwrap().fingerprint, b.fingerprint);
+        assert_eq!(reg.get(&fp).unwrap().status, VkStatus::Retired);
+        let _ = fs::remove_file(&p);
+    }
+
+    #[test]
+    fn governanca_com_aprovador_repetido_nao_carrega() {
+        let p = arquivo("gov_repetido");
+        let (mut gov, _) = governanca();
+        gov.approvers[2].public_key = gov.approvers[0].public_key.clone();
+        fs::write(&p, serde_json::to_string(&gov).unwrap()).unwrap();
+        assert!(Governance::load(Path::new(&p)).err().unwrap().contains("repetido"));
+
+        gov.threshold = 4;
+        fs::write(&p, serde_json::to_string(&gov).unwrap()).unwrap();
+        assert!(Governance::load(Path::new(&p)).err().unwrap().contains("threshold"));
+        let _ = fs::remove_file(&p);
+    }
+}
@@ -0,0 +1,430 @@
+// =========================================================
+// 🧮 ZK VERIFY — verificação local das provas Halo2 do prover
+// =========================================================
+//
+// Circuito td.fp.v1 (Halo2 IPA sobre Pasta, sem trusted setup):
+//   advice[0] = testemunha,   instance[0] = Fp (pqc_sign_hash_to_fp)
+//   copy constraint testemunha == instância
+//
+// A prova amarra o Fp público ao payload assinado ("PQC-Rooted ZK proof.md").
+// O circuito é só isso: não prova nada sobre o payload além do Fp. É o
+// contrato com o prover ("Prover Protocol.md" §VK) — o prover tem que
+// provar exatamente este circuito (mesmas colunas, mesma região, mesma
+// constraint). VK de qualquer outro circuito é recusada no registro: o
+// repr dela não bate com o da VK refeita daqui.
+//
+// halo2_proofs 0.3 não tem VerifyingKey::write/read nem expõe os
+// commitments da VK. A VK é serializada num formato próprio, binário e
+// versionado (nada de Debug):
+//
+//   "TDVK" | formato u8 (1) | len u8 | circuit id | k u32 LE | repr [32]
+//
+// repr é o escalar que o próprio halo2 absorve no transcript de toda prova
+// (VerifyingKey::hash_into): duas VKs com o mesmo repr aceitam exatamente
+// as mesmas provas. read_vk::<C> reconstrói a VK do circuito concreto C
+// com o k lido e só a aceita se o repr bater.
+//
+// keygen custa Params::new(k) + keygen_vk: o ZkVerifier guarda o repr
+// esperado por k e recusa bytes com outro repr sem refazer a VK, e lembra
+// os fingerprints já recusados.
+//
+//   vk_fingerprint = hex(SHA-256(vk_bytes))
+
+use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner, Value};
+use halo2_proofs::plonk::{
+    keygen_vk, verify_proof, Advice, Circuit, Column, ConstraintSystem, Error, Instance,
+    SingleVerifier, VerifyingKey,
+};
+use halo2_proofs::poly::commitment::Params;
+use halo2_proofs::transcript::{Blake2bRead, Challenge255, EncodedChallenge, Transcript};
+use pasta_curves::group::ff::PrimeField;
+use pasta_curves::pallas::Base as Fp;
+use pasta_curves::vesta::Affine as EqAffine;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+pub const CIRCUIT_ID: &str = "td.fp.v1";
+pub const DEFAULT_K: u32 = 4;
+/// Params::new(k) custa 2^k pontos: VKs com k maior são recusadas.
+pub const MAX_K: u32 = 16;
+
+pub const VK_MAGIC: &[u8; 4] = b"TDVK";
+pub const VK_FORMAT: u8 = 1;
+
+/// Fingerprints recusados lembrados pelo ZkVerifier (depois, zera).
+const MAX_REJECTED: usize = 1024;
+
+// =========================================================
+// CIRCUITO
+// =========================================================
+
+#[derive(Clone, Default)]
+pub struct FpCircuit {
+    pub fp: Value<Fp>,
+}
+
+#[derive(Clone)]
+pub struct FpConfig {
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Circuito com identidade estável, para read_vk::<C>.
+pub trait IdentifiedCircuit: Circuit<Fp> + Default {
+    const ID: &'static str;
+}
+
+impl IdentifiedCircuit for FpCircuit {
+    const ID: &'static str = CIRCUIT_ID;
+}
+
+impl Circuit<Fp> for FpCircuit {
+    type Config = FpConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> FpConfig {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        FpConfig { advice, instance }
+    }
+
+    fn synthesize(&self, config: FpConfig, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        let cell = layouter.assign_region(
+            || "fp",
+            |mut region| region.assign_advice(|| "fp", config.advice, 0, || self.fp),
+        )?;
+        layouter.constrain_instance(cell.cell(), config.instance, 0)
+    }
+}
+
+// =========================================================
+// SERIALIZAÇÃO DA VK
+// =========================================================
+
+/// Transcript que só guarda o escalar comum absorvido por hash_into.
+#[derive(Default)]
+struct ReprTranscript {
+    repr: Option<Fp>,
+}
+
+impl Transcript<EqAffine, Challenge255<EqAffine>> for ReprTranscript {
+    fn squeeze_challenge(&mut self) -> Challenge255<EqAffine> {
+        Challenge255::new(&[0u8; 64])
+    }
+
+    fn common_point(&mut self, _point: EqAffine) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn common_scalar(&mut self, scalar: Fp) -> io::Result<()> {
+        self.repr = Some(scalar);
+        Ok(())
+    }
+}
+
+/// O escalar que representa a VK no transcript das provas.
+fn vk_repr(vk: &VerifyingKey<EqAffine>) -> [u8; 32] {
+    let mut t = ReprTranscript::default();
+    // ReprTranscript nunca falha
+    let _ = vk.hash_into(&mut t);
+    t.repr.unwrap_or_default().to_repr()
+}
+
+/// Escreve a VK de `C` com parâmetro `k` no formato TDVK.
+pub fn write_vk<C: IdentifiedCircuit, W: io::Write>(
+    k: u32,
+    vk: &VerifyingKey<EqAffine>,
+    writer: &mut W,
+) -> io::Result<()> {
+    let id = C::ID.as_bytes();
+    let id_len = u8::try_from(id.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "circuit id longo demais"))?;
+    writer.write_all(VK_MAGIC)?;
+    writer.write_all(&[VK_FORMAT, id_len])?;
+    writer.write_all(id)?;
+    writer.write_all(&k.to_le_bytes())?;
+    writer.write_all(&vk_repr(vk))
+}
+
+/// VK lida de bytes TDVK, pronta para verificar provas.
+pub struct LoadedVk {
+    circuit: &'static str,
+    k: u32,
+    params: Params<EqAffine>,
+    vk: VerifyingKey<EqAffine>,
+    fingerprint: String,
+}
+
+impl LoadedVk {
+    pub fn circuit(&self) -> &str {
+        self.circuit
+    }
+
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    pub fn verify(&self, proof: &[u8], fp: Fp) -> Result<(), String> {
+        let instance = [fp];
+        let instances: &[&[Fp]] = &[&instance];
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+        verify_proof(
+            &self.params,
+            &self.vk,
+            SingleVerifier::new(&self.params),
+            &[instances],
+            &mut transcript,
+        )
+        .map_err(|e| format!("{:?}", e))
+    }
+}
+
+/// Cabeçalho TDVK de `C`, sem keygen: (k, repr).
+fn parse_vk<C: IdentifiedCircuit>(bytes: &[u8]) -> Result<(u32, [u8; 32]), String> {
+    let err = |msg: String| format!("VK {}: {}", vk_fingerprint(bytes), msg);
+
+    let rest = bytes
+        .strip_prefix(VK_MAGIC.as_slice())
+        .ok_or_else(|| err("não está no formato TDVK (registre a VK de novo)".into()))?;
+    let (&format, rest) = rest.split_first().ok_or_else(|| err("truncada".into()))?;
+    if format != VK_FORMAT {
+        return Err(err(format!("formato {} desconhecido", format)));
+    }
+    let (&id_len, rest) = rest.split_first().ok_or_else(|| err("truncada".into()))?;
+    let id_len = id_len as usize;
+    if rest.len() != id_len + 4 + 32 {
+        return Err(err(format!("{} bytes após o cabeçalho, esperado {}", rest.len(), id_len + 36)));
+    }
+    let (id, rest) = rest.split_at(id_len);
+    if id != C::ID.as_bytes() {
+        return Err(err(format!("circuito {:?}, esperado {}", String::from_utf8_lossy(id), C::ID)));
+    }
+    let (k, repr) = rest.split_at(4);
+    let k = u32::from_le_bytes(k.try_into().unwrap());
+    if k == 0 || k > MAX_K {
+        return Err(err(format!("k={} fora de 1..={}", k, MAX_K)));
+    }
+    Ok((k, repr.try_into().unwrap()))
+}
+
+/// Lê bytes TDVK como VK do circuito `C`: o circuit id tem que ser o de
+/// `C`, e a VK refeita de `C` com o k lido tem que ter o mesmo repr.
+pub fn read_vk<C: IdentifiedCircuit>(bytes: &[u8]) -> Result<LoadedVk, String> {
+    let (k, repr) = parse_vk::<C>(bytes)?;
+    let (params, vk) = keygen::<C>(k)?;
+    if vk_repr(&vk) != repr {
+        return Err(format!("VK {}: não é a VK do circuito {} com k={}", vk_fingerprint(bytes), C::ID, k));
+    }
+    Ok(LoadedVk { circuit: C::ID, k, params, vk, fingerprint: vk_fingerprint(bytes) })
+}
+
+fn keygen<C: IdentifiedCircuit>(k: u32) -> Result<(Params<EqAffine>, VerifyingKey<EqAffine>), String> {
+    let params: Params<EqAffine> = Params::new(k);
+    let vk = keygen_vk(&params, &C::default()).map_err(|e| format!("keygen_vk({}, k={}): {:?}", C::ID, k, e))?;
+    Ok((params, vk))
+}
+
+// =========================================================
+// VERIFICADOR
+// =========================================================
+
+pub fn vk_fingerprint(vk_bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(vk_bytes))
+}
+
+/// 32 bytes little-endian → Fp, recusando representações fora do corpo.
+pub fn fp_from_bytes(bytes: &[u8]) -> Option<Fp> {
+    let repr: [u8; 32] = bytes.try_into().ok()?;
+    Option::from(Fp::from_repr(repr))
+}
+
+/// Verificador do circuito local. Guarda as VKs já lidas por fingerprint:
+/// keygen só roda uma vez por k, e nunca para bytes já recusados.
+pub struct ZkVerifier {
+    k: u32,
+    vk_bytes: Vec<u8>,
+    fingerprint: String,
+    loaded: Mutex<HashMap<String, Arc<LoadedVk>>>,
+    /// k → repr da VK do circuito local (checado antes do keygen)
+    reprs: Mutex<HashMap<u32, [u8; 32]>>,
+    rejected: Mutex<HashMap<String, String>>,
+}
+
+impl ZkVerifier {
+    /// `k` é só o da VK esperada do prover (log, vk_bytes); VKs
+    /// registradas com outro k também são aceitas.
+    pub fn new(k: u32) -> Result<Self, String> {
+        let (_, vk) = keygen::<FpCircuit>(k)?;
+        let mut vk_bytes = Vec::new();
+        write_vk::<FpCircuit, _>(k, &vk, &mut vk_bytes).map_err(|e| format!("write_vk: {}", e))?;
+        let fingerprint = vk_fingerprint(&vk_bytes);
+        Ok(Self {
+            k,
+            vk_bytes,
+            fingerprint,
+            loaded: Mutex::new(HashMap::new()),
+            reprs: Mutex::new(HashMap::from([(k, vk_repr(&vk))])),
+            rejected: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    /// Bytes TDVK da VK do circuito local com o k configurado.
+    pub fn vk_bytes(&self) -> &[u8] {
+        &self.vk_bytes
+    }
+
+    /// Fingerprint da VK esperada (identidade canônica do circuito/k).
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Lê (ou pega do cache) a VK registrada como VK do circuito local.
+    /// Pode rodar keygen (segundos com k alto): em código async, use
+    /// `load_blocking`.
+    pub fn load(&self, bytes: &[u8]) -> Result<Arc<LoadedVk>, String> {
+        let fingerprint = vk_fingerprint(bytes);
+        if let Some(vk) = self.loaded.lock().unwrap().get(&fingerprint) {
+            return Ok(vk.clone());
+        }
+        if let Some(e) = self.rejected.lock().unwrap().get(&fingerprint) {
+            return Err(e.clone());
+        }
+
+        let result = parse_vk::<FpCircuit>(bytes).and_then(|(k, repr)| {
+            let known = self.reprs.lock().unwrap().get(&k).copied();
+            match known {
+                Some(expected) if expected != repr => {
+                    Err(format!("VK {}: não é a VK do circuito {} com k={}", fingerprint, CIRCUIT_ID, k))
+                }
+                _ => read_vk::<FpCircuit>(bytes),
+            }
+        });
+
+        match result {
+            Ok(vk) => {
+                let vk = Arc::new(vk);
+                self.reprs.lock().unwrap().insert(vk.k, vk_repr(&vk.vk));
+                self.loaded.lock().unwrap().insert(fingerprint, vk.clone());
+                Ok(vk)
+            }
+            Err(e) => {
+                let mut rejected = self.rejected.lock().unwrap();
+                if rejected.len() >= MAX_REJECTED {
+                    rejected.clear();
+                }
+                rejected.insert(fingerprint, e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    /// `load` numa thread de bloqueio: o keygen não trava o worker do tokio.
+    pub async fn load_blocking(self: Arc<Self>, bytes: Vec<u8>) -> Result<Arc<LoadedVk>, String> {
+        tokio::task::spawn_blocking(move || self.load(&bytes))
+            .await
+            .map_err(|e| format!("leitura da VK abortada: {}", e))?
+    }
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::plonk::{create_proof, keygen_pk};
+    use halo2_proofs::transcript::Blake2bWrite;
+    use rand::rngs::OsRng;
+
+    fn provar(k: u32, fp: Fp) -> (Vec<u8>, Vec<u8>) {
+        let params: Params<EqAffine> = Params::new(k);
+        let vk = keygen_vk(&params, &FpCircuit::default()).unwrap();
+        let mut vk_bytes = Vec::new();
+        write_vk::<FpCircuit, _>(k, &vk, &mut vk_bytes).unwrap();
+
+        let pk = keygen_pk(&params, vk, &FpCircuit::default()).unwrap();
+        let circuit = FpCircuit { fp: Value::known(fp) };
+        let instance = [fp];
+        let instances: &[&[Fp]] = &[&instance];
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(&params, &pk, &[circuit], &[instances], OsRng, &mut transcript).unwrap();
+        (vk_bytes, transcript.finalize())
+    }
+
+    #[test]
+    fn prova_verifica_com_vk_lida() {
+        let fp = Fp::from(42);
+        let (vk_bytes, proof) = provar(5, fp);
+
+        let zk = ZkVerifier::new(DEFAULT_K).unwrap();
+        let vk = zk.load(&vk_bytes).unwrap();
+        assert_eq!(vk.k(), 5);
+        assert_eq!(vk.circuit(), CIRCUIT_ID);
+        assert_eq!(vk.fingerprint(), vk_fingerprint(&vk_bytes));
+        assert!(vk.verify(&proof, fp).is_ok());
+        assert!(vk.verify(&proof, Fp::from(43)).is_err());
+
+        // mesma prova contra a VK de outro k
+        assert!(zk.load(zk.vk_bytes()).unwrap().verify(&proof, fp).is_err());
+    }
+
+    #[test]
+    fn bytes_da_vk_sao_estaveis() {
+        let a = ZkVerifier::new(DEFAULT_K).unwrap();
+        let b = ZkVerifier::new(DEFAULT_K).unwrap();
+        assert_eq!(a.vk_bytes(), b.vk_bytes());
+        assert_eq!(&a.vk_bytes()[..4], VK_MAGIC);
+        assert_eq!(a.vk_bytes().len(), 4 + 2 + CIRCUIT_ID.len() + 4 + 32);
+    }
+
+    #[test]
+    fn vk_adulterada_recusada() {
+        let zk = ZkVerifier::new(DEFAULT_K).unwrap();
+        let bytes = zk.vk_bytes().to_vec();
+
+        let mut repr = bytes.clone();
+        *repr.last_mut().unwrap() ^= 1;
+        assert!(read_vk::<FpCircuit>(&repr).is_err());
+
+        let mut k = bytes.clone();
+        let pos = 6 + CIRCUIT_ID.len();
+        k[pos] += 1;
+        assert!(read_vk::<FpCircuit>(&k).is_err());
+
+        assert!(read_vk::<FpCircuit>(&bytes[..bytes.len() - 1]).is_err());
+        assert!(read_vk::<FpCircuit>(b"PinnedVerificationKey { .. }").is_err());
+    }
+
+    #[test]
+    fn repr_errado_recusado_sem_keygen() {
+        let zk = ZkVerifier::new(DEFAULT_K).unwrap();
+        let mut bytes = zk.vk_bytes().to_vec();
+        *bytes.last_mut().unwrap() ^= 1;
+
+        let err = zk.load(&bytes).err().unwrap();
+        assert!(err.contains("não é a VK"), "{}", err);
+        // recusa lembrada; nenhuma VK carregada nem repr novo
+        assert_eq!(zk.rejected.lock().unwrap().get(&vk_fingerprint(&bytes)), Some(&err));
+        assert!(zk.loaded.lock().unwrap().is_empty());
+        assert_eq!(zk.reprs.lock().unwrap().len(), 1);
+        assert_eq!(zk.load(&bytes).err().unwrap(), err);
+
+        assert!(zk.load(zk.vk_bytes()).is_ok());
+    }
+}
@@ -0,0 +1,114 @@
+// =========================================================
+// 📐 CANONICAL CHECK — confere os vetores golden do JCS
+// =========================================================
+//
+// uso: canonical_check [canonical_vectors.json]
+//
+// cases: `input` (texto JSON cru) → `canonical` esperado, ou `error`
+// mel:   `claim` (texto JSON cru) + `timestamp` → `payload` e SHA-256
+//
+// Qualquer implementação externa (auditor, app) deve passar nos mesmos
+// vetores para reconstruir o payload assinado pelo servidor.
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{env, fs, process};
+
+use terra_dourada_core::canonical::{canonicalize, CanonicalError};
+
+#[derive(Deserialize)]
+struct Vectors {
+    cases: Vec<Case>,
+    mel: Vec<MelCase>,
+}
+
+#[derive(Deserialize)]
+struct Case {
+    name: String,
+    input: String,
+    canonical: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MelCase {
+    name: String,
+    claim: String,
+    timestamp: u64,
+    payload: String,
+    payload_sha256: String,
+}
+
+fn error_name(e: &CanonicalError) -> &'static str {
+    match e {
+        CanonicalError::UnsafeInteger(_) => "UnsafeInteger",
+        CanonicalError::NonFinite => "NonFinite",
+    }
+}
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "canonical_vectors.json".into());
+
+    let txt = fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao ler {}: {}", path, e);
+        process::exit(2);
+    });
+
+    let vectors: Vectors = serde_json::from_str(&txt).unwrap_or_else(|e| {
+        eprintln!("❌ vetores inválidos: {}", e);
+        process::exit(2);
+    });
+
+    let mut falhas = 0;
+
+    println!("📐 JCS — {} casos", vectors.cases.len());
+
+    for case in &vectors.cases {
+        let got = serde_json::from_str(&case.input)
+            .map_err(|e| format!("JSON inválido: {}", e))
+            .and_then(|v| canonicalize(&v).map_err(|e| error_name(&e).to_string()));
+
+        let ok = match (&got, &case.canonical, &case.error) {
+            (Ok(s), Some(want), _) => s == want,
+            (Err(e), None, Some(want)) => e == want,
+            _ => false,
+        };
+
+        if ok {
+            println!("✅ {}", case.name);
+        } else {
+            falhas += 1;
+            println!("❌ {}: obtido {:?}", case.name, got);
+        }
+    }
+
+    println!("🧾 Payload /mel — {} casos", vectors.mel.len());
+
+    for case in &vectors.mel {
+        let payload = serde_json::from_str(&case.claim)
+            .map_err(|e| e.to_string())
+            .and_then(|v| canonicalize(&v).map_err(|e| e.to_string()))
+            .map(|c| format!("{}:{}", c, case.timestamp));
+
+        match payload {
+            Ok(p) if p == case.payload
+                && hex::encode(Sha256::digest(p.as_bytes())) == case.payload_sha256 =>
+            {
+                println!("✅ {}", case.name);
+            }
+            other => {
+                falhas += 1;
+                println!("❌ {}: obtido {:?}", case.name, other);
+            }
+        }
+    }
+
+    if falhas > 0 {
+        eprintln!("❌ {} vetores falharam", falhas);
+        process::exit(1);
+    }
+
+    println!("✅ Todos os vetores conferem");
+}
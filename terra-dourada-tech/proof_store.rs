@@ -0,0 +1,338 @@
+// =========================================================
+// 📚 PROOF STORE — histórico append-only das provas aceitas
+// =========================================================
+//
+// Cada prova aceita pelo POST /mel vira uma linha JSON no arquivo
+// (JSONL). O arquivo nunca é reescrito: só cresce. No startup ele é
+// relido inteiro para a memória, e o GET /mel consulta essa cópia.
+//
+// Um crash no meio do write deixa a última linha sem `\n`: ela vai para
+// {arquivo}.torn e o arquivo é truncado no último `\n` (como o ledger),
+// para o próximo append não colar nela. Linha inválida no meio do
+// arquivo é corrupção: o store não abre.
+//
+// Linhas gravadas antes dos schemas tipados (`vote: { voter_id,
+// candidate, ... }`) são lidas como `vote.v1` com ruleset_id
+// LEGACY_VOTE_RULESET: o hash delas cobre o payload antigo, não o claim.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use terra_dourada_core::report_schema::{Report, VoteV1};
+
+use crate::Proof;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// ruleset_id das provas de voto anteriores aos schemas tipados.
+pub const LEGACY_VOTE_RULESET: &str = "vote.legacy";
+
+/// Linha do formato antigo: `{ urn_id, vote, timestamp, hash, proof, cid }`.
+#[derive(Deserialize)]
+struct LegacyProof {
+    urn_id: String,
+    vote: LegacyVote,
+    timestamp: u64,
+    hash: Vec<u8>,
+    proof: String,
+    cid: String,
+}
+
+#[derive(Deserialize)]
+struct LegacyVote {
+    voter_id: String,
+    candidate: String,
+    #[serde(flatten)]
+    extra_fields: Map<String, Value>,
+}
+
+impl From<LegacyProof> for Proof {
+    fn from(old: LegacyProof) -> Self {
+        let extra = |k: &str| old.vote.extra_fields.get(k).and_then(|v| v.as_str()).map(String::from);
+        Proof {
+            report: Report::Vote(VoteV1 {
+                autor: old.vote.voter_id.clone(),
+                candidato: old.vote.candidate.clone(),
+                eleicao_id: extra("eleicao_id"),
+                screenshot_hash: extra("screenshot_hash"),
+            }),
+            urn_id: old.urn_id,
+            ruleset_id: LEGACY_VOTE_RULESET.into(),
+            timestamp: old.timestamp,
+            hash: old.hash,
+            proof: old.proof,
+            cid: old.cid,
+            key_id: None,
+            vk_fingerprint: None,
+            signature: None,
+        }
+    }
+}
+
+/// Formato atual; se não for, tenta o antigo. O erro reportado é o do
+/// formato atual.
+fn decode(line: &str) -> Result<Proof, serde_json::Error> {
+    serde_json::from_str::<Proof>(line).or_else(|e| {
+        serde_json::from_str::<LegacyProof>(line)
+            .map(Proof::from)
+            .map_err(|_| e)
+    })
+}
+
+pub struct ProofStore {
+    path: PathBuf,
+    proofs: Vec<Proof>,
+}
+
+impl ProofStore {
+    /// Abre (ou cria) o arquivo e carrega todo o histórico.
+    /// Uma última linha truncada (crash no meio do write) sai do arquivo
+    /// para {arquivo}.torn; qualquer outra linha inválida é erro.
+    pub fn open(path: &str) -> Result<Self, String> {
+        let path = PathBuf::from(path);
+
+        if let Some(p) = path.parent() {
+            if !p.as_os_str().is_empty() {
+                fs::create_dir_all(p)
+                    .map_err(|e| format!("falha create_dir_all({:?}): {}", p, e))?;
+            }
+        }
+
+        let mut proofs = Vec::new();
+
+        if Path::new(&path).exists() {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&path)
+                .map_err(|e| format!("falha ao abrir {:?}: {}", path, e))?;
+
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)
+                .map_err(|e| format!("falha ao ler {:?}: {}", path, e))?;
+
+            let keep = bytes.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+            if keep < bytes.len() {
+                set_aside(&path, &bytes[keep..])?;
+                file.set_len(keep as u64)
+                    .and_then(|_| file.sync_all())
+                    .map_err(|e| format!("falha ao truncar {:?}: {}", path, e))?;
+                eprintln!("🩹 proof_store: {} bytes de linha rasgada movidos para .torn", bytes.len() - keep);
+            }
+
+            let text = std::str::from_utf8(&bytes[..keep])
+                .map_err(|e| format!("{:?} não é UTF-8: {}", path, e))?;
+            for (i, line) in text.lines().enumerate() {
+                let l = line.trim();
+                if l.is_empty() {
+                    continue;
+                }
+                let proof = decode(l)
+                    .map_err(|e| format!("{:?} linha {} corrompida: {}", path, i + 1, e))?;
+                proofs.push(proof);
+            }
+        }
+
+        Ok(Self { path, proofs })
+    }
+
+    /// Grava a prova no disco (fsync) e só então expõe na memória.
+    pub fn append(&mut self, proof: Proof) -> Result<(), String> {
+        let line = serde_json::to_string(&proof)
+            .map_err(|e| format!("falha serializando prova: {}", e))?;
+
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("falha ao abrir {:?}: {}", self.path, e))?;
+
+        writeln!(f, "{}", line).map_err(|e| format!("falha ao escrever prova: {}", e))?;
+        f.sync_data().map_err(|e| format!("falha no fsync: {}", e))?;
+
+        self.proofs.push(proof);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    /// Filtra por intervalo de tempo, autor, candidato e schema, e pagina.
+    pub fn query(&self, q: &MelQuery) -> MelPage<'_> {
+        let matches: Vec<&Proof> = self
+            .proofs
+            .iter()
+            .filter(|p| q.from.is_none_or(|from| p.timestamp >= from))
+            .filter(|p| q.to.is_none_or(|to| p.timestamp <= to))
+            .filter(|p| q.autor.as_deref().is_none_or(|a| p.report.autor() == a))
+            .filter(|p| q.candidato.as_deref().is_none_or(|c| p.report.candidato() == Some(c)))
+            .filter(|p| q.schema.as_deref().is_none_or(|s| p.report.schema() == s))
+            .collect();
+
+        let offset = q.offset.unwrap_or(0);
+        let limit = q.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+        MelPage {
+            total: matches.len(),
+            offset,
+            limit,
+            items: matches.into_iter().skip(offset).take(limit).collect(),
+        }
+    }
+}
+
+/// Guarda a linha rasgada em {arquivo}.torn antes de truncar.
+fn set_aside(path: &Path, torn: &[u8]) -> Result<(), String> {
+    let mut torn_path = path.as_os_str().to_owned();
+    torn_path.push(".torn");
+
+    let mut aside = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&torn_path)
+        .map_err(|e| format!("falha ao abrir {:?}: {}", torn_path, e))?;
+    aside
+        .write_all(torn)
+        .and_then(|_| aside.write_all(b"\n"))
+        .and_then(|_| aside.sync_all())
+        .map_err(|e| format!("falha ao gravar {:?}: {}", torn_path, e))
+}
+
+// =========================================================
+// GET /mel — query string
+// =========================================================
+
+#[derive(Deserialize, Default, Debug)]
+pub struct MelQuery {
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+    /// timestamp mínimo (segundos, inclusivo)
+    pub from: Option<u64>,
+    /// timestamp máximo (segundos, inclusivo)
+    pub to: Option<u64>,
+    pub autor: Option<String>,
+    pub candidato: Option<String>,
+    /// ex.: iot_reading.v1
+    pub schema: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct MelPage<'a> {
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub items: Vec<&'a Proof>,
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arquivo(nome: &str) -> PathBuf {
+        let mut p = std::env::temp_dir();
+        p.push(format!("proof_store_{}_{}.jsonl", std::process::id(), nome));
+        let _ = fs::remove_file(&p);
+        p
+    }
+
+    fn prova(candidato: &str) -> Proof {
+        Proof {
+            urn_id: "TERRADOURADA_URN_01".into(),
+            report: Report::Vote(VoteV1 {
+                autor: "ana".into(),
+                candidato: candidato.into(),
+                eleicao_id: None,
+                screenshot_hash: None,
+            }),
+            ruleset_id: "vote.v1/r1".into(),
+            timestamp: 1_000,
+            hash: vec![1, 2, 3],
+            proof: "cHJvdmE=".into(),
+            cid: "bafkteste".into(),
+            key_id: None,
+            vk_fingerprint: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn linha_rasgada_sai_do_arquivo() {
+        let p = arquivo("rasgada");
+        let mut store = ProofStore::open(p.to_str().unwrap()).unwrap();
+        store.append(prova("a")).unwrap();
+
+        // crash no meio do write seguinte
+        let mut f = OpenOptions::new().append(true).open(&p).unwrap();
+        f.write_all(br#"{"urn_id":"TERRADOURADA_URN_01","rep"#).unwrap();
+        drop(f);
+
+        let mut store = ProofStore::open(p.to_str().unwrap()).unwrap();
+        assert_eq!(store.len(), 1);
+        store.append(prova("b")).unwrap();
+
+        // a prova aceita depois do crash sobrevive ao restart
+        let store = ProofStore::open(p.to_str().unwrap()).unwrap();
+        assert_eq!(store.len(), 2);
+        let torn = format!("{}.torn", p.display());
+        assert!(fs::read_to_string(&torn).unwrap().contains("\"rep"));
+        let _ = fs::remove_file(&p);
+        let _ = fs::remove_file(&torn);
+    }
+
+    #[test]
+    fn linha_corrompida_no_meio_nao_abre() {
+        let p = arquivo("corrompida");
+        let mut store = ProofStore::open(p.to_str().unwrap()).unwrap();
+        store.append(prova("a")).unwrap();
+
+        let mut f = OpenOptions::new().append(true).open(&p).unwrap();
+        f.write_all(b"{lixo}\n").unwrap();
+        drop(f);
+        store.append(prova("b")).unwrap();
+
+        let err = ProofStore::open(p.to_str().unwrap()).err().expect("abriu arquivo corrompido");
+        assert!(err.contains("linha 2"), "{}", err);
+        let _ = fs::remove_file(&p);
+    }
+
+    #[test]
+    fn linha_antiga_de_voto_vira_vote_v1() {
+        let p = arquivo("legado");
+        fs::write(
+            &p,
+            concat!(
+                r#"{"urn_id":"TERRADOURADA_URN_01","vote":{"voter_id":"ana","candidate":"mel","eleicao_id":"e1","nota":5},"#,
+                r#""timestamp":900,"hash":[9,9],"proof":"cHJvdmE=","cid":"bafkantigo"}"#,
+                "\n"
+            ),
+        )
+        .unwrap();
+
+        let mut store = ProofStore::open(p.to_str().unwrap()).unwrap();
+        store.append(prova("b")).unwrap();
+        let store = ProofStore::open(p.to_str().unwrap()).unwrap();
+        assert_eq!(store.len(), 2);
+
+        let antiga = &store.proofs[0];
+        assert_eq!(antiga.ruleset_id, LEGACY_VOTE_RULESET);
+        assert_eq!(antiga.timestamp, 900);
+        assert_eq!(antiga.hash, vec![9, 9]);
+        let Report::Vote(v) = &antiga.report else { panic!("esperava vote.v1") };
+        assert_eq!((v.autor.as_str(), v.candidato.as_str()), ("ana", "mel"));
+        assert_eq!(v.eleicao_id.as_deref(), Some("e1"));
+
+        // filtros do GET /mel enxergam a prova antiga
+        let q = MelQuery { candidato: Some("mel".into()), schema: Some("vote.v1".into()), ..Default::default() };
+        assert_eq!(store.query(&q).total, 1);
+        let _ = fs::remove_file(&p);
+    }
+}
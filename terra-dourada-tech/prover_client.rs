@@ -0,0 +1,260 @@
+// =========================================================
+// 🤝 PROVER CLIENT — challenge-response assinado com o prover
+// =========================================================
+//
+// pedido  → { request_id, nonce, fp_bytes, ruleset_id, original, expires_at }
+// resposta← { nonce, proof_b64, signature }
+//
+//   transcript = "td.prover.v1:" || nonce(32) || fp_bytes(32) || SHA-256(proof)(32)
+//   signature  = Dilithium2.Sign(sk_prover, transcript)
+//
+// Cada tentativa usa um nonce novo. A resposta só é aceita se ecoar o
+// nonce da tentativa, chegar antes de `expires_at` e trazer assinatura
+// válida da chave do prover (TD_PROVER_PUBKEY). Um servidor que só
+// devolve o que recebeu não passa: não tem a chave e não conhece a prova.
+//
+// Só erros de transporte (timeout, conexão, 5xx) são repetidos; resposta
+// adulterada ou fora de contexto derruba o pedido na hora.
+
+use base64::engine::{general_purpose, Engine as _};
+use crystals_dilithium::dilithium2::{PublicKey, PUBLICKEYBYTES, SIGNBYTES};
+use rand::RngCore;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use terra_dourada_core::config::UpstreamConfig;
+use terra_dourada_core::keystore::key_id_for;
+
+pub const TRANSCRIPT_DOMAIN: &str = "td.prover.v1";
+
+const RETRY_BASE_MS: u64 = 500;
+
+#[derive(Clone)]
+pub struct ProverConfig {
+    pub url: String,
+    pub public_key: Vec<u8>,
+    pub timeout: Duration,
+    pub retries: u32,
+    pub max_age_secs: u64,
+}
+
+impl ProverConfig {
+    /// URL, retries e idade máxima vêm do config.rs ([upstream]), o timeout
+    /// de timeouts.prover_ms; do ambiente só TD_PROVER_PUBKEY (base64,
+    /// obrigatória).
+    pub fn from_env(upstream: &UpstreamConfig, timeout: Duration) -> Result<Self, String> {
+        let pk_b64 = env::var("TD_PROVER_PUBKEY")
+            .map_err(|_| "TD_PROVER_PUBKEY não definida (chave Dilithium2 do prover, base64)".to_string())?;
+        let public_key = general_purpose::STANDARD
+            .decode(pk_b64.trim())
+            .map_err(|e| format!("TD_PROVER_PUBKEY inválida: {}", e))?;
+        if public_key.len() != PUBLICKEYBYTES {
+            return Err(format!(
+                "TD_PROVER_PUBKEY com {} bytes (esperado {})",
+                public_key.len(),
+                PUBLICKEYBYTES
+            ));
+        }
+
+        Ok(Self {
+            url: upstream.prover_url.clone(),
+            public_key,
+            timeout,
+            retries: upstream.prover_retries,
+            max_age_secs: upstream.prover_max_age_secs,
+        })
+    }
+
+    pub fn key_id(&self) -> String {
+        key_id_for(&self.public_key)
+    }
+}
+
+#[derive(Debug)]
+pub enum ProverError {
+    Transport(String),
+    Status(u16, String),
+    Malformed(String),
+    NonceMismatch,
+    Stale,
+    BadSignature,
+}
+
+impl ProverError {
+    fn retryable(&self) -> bool {
+        match self {
+            ProverError::Transport(_) => true,
+            ProverError::Status(code, _) => *code >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ProverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProverError::Transport(e) => write!(f, "falha de transporte: {}", e),
+            ProverError::Status(code, body) => write!(f, "prover respondeu HTTP {}: {}", code, body),
+            ProverError::Malformed(e) => write!(f, "resposta malformada: {}", e),
+            ProverError::NonceMismatch => write!(f, "nonce da resposta não é o do pedido"),
+            ProverError::Stale => write!(f, "resposta chegou depois de expires_at"),
+            ProverError::BadSignature => write!(f, "assinatura do prover não confere"),
+        }
+    }
+}
+
+/// Prova aceita + o que um auditor precisa para refazer a checagem.
+pub struct ProverOutput {
+    pub proof_b64: String,
+    pub proof: Vec<u8>,
+    pub proof_sha256: [u8; 32],
+    pub nonce: [u8; 32],
+    pub signature_b64: String,
+    pub attempts: u32,
+}
+
+#[derive(Deserialize)]
+struct ProveResponse {
+    nonce: String,
+    proof_b64: String,
+    signature: String,
+}
+
+pub fn transcript(nonce: &[u8; 32], fp_bytes: &[u8], proof_sha256: &[u8; 32]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(TRANSCRIPT_DOMAIN.len() + 1 + 32 + fp_bytes.len() + 32);
+    msg.extend_from_slice(TRANSCRIPT_DOMAIN.as_bytes());
+    msg.push(b':');
+    msg.extend_from_slice(nonce);
+    msg.extend_from_slice(fp_bytes);
+    msg.extend_from_slice(proof_sha256);
+    msg
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[derive(Clone)]
+pub struct ProverClient {
+    cfg: ProverConfig,
+    http: reqwest::Client,
+}
+
+impl ProverClient {
+    pub fn new(cfg: ProverConfig) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .timeout(cfg.timeout)
+            .build()
+            .map_err(|e| format!("falha criando cliente HTTP do prover: {}", e))?;
+        Ok(Self { cfg, http })
+    }
+
+    pub fn config(&self) -> &ProverConfig {
+        &self.cfg
+    }
+
+    /// Pede a prova de `fp_bytes`, repetindo só falhas de transporte.
+    /// `request_id` (a tag DK-PQC-HMAC) só correlaciona logs; não autentica nada.
+    pub async fn prove(
+        &self,
+        request_id: &str,
+        fp_bytes: &[u8],
+        ruleset_id: &str,
+        original: &str,
+    ) -> Result<ProverOutput, ProverError> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.attempt(request_id, fp_bytes, ruleset_id, original).await {
+                Ok(mut out) => {
+                    out.attempts = attempt;
+                    return Ok(out);
+                }
+                Err(e) if e.retryable() && attempt <= self.cfg.retries => {
+                    let wait = RETRY_BASE_MS << (attempt - 1).min(6);
+                    println!("⚠️  Prover tentativa {} falhou ({}); nova tentativa em {} ms", attempt, e, wait);
+                    tokio::time::sleep(Duration::from_millis(wait)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn attempt(
+        &self,
+        request_id: &str,
+        fp_bytes: &[u8],
+        ruleset_id: &str,
+        original: &str,
+    ) -> Result<ProverOutput, ProverError> {
+        let mut nonce = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let expires_at = unix_now() + self.cfg.max_age_secs;
+
+        let body = serde_json::json!({
+            "request_id": request_id,
+            "nonce": hex::encode(nonce),
+            "fp_bytes": fp_bytes,
+            "ruleset_id": ruleset_id,
+            "original": original,
+            "expires_at": expires_at
+        });
+
+        let resp = self
+            .http
+            .post(&self.cfg.url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| ProverError::Transport(e.to_string()))?;
+
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| ProverError::Transport(e.to_string()))?;
+        if !status.is_success() {
+            return Err(ProverError::Status(status.as_u16(), text));
+        }
+
+        if unix_now() > expires_at {
+            return Err(ProverError::Stale);
+        }
+
+        let parsed: ProveResponse =
+            serde_json::from_str(&text).map_err(|e| ProverError::Malformed(e.to_string()))?;
+
+        if hex::decode(&parsed.nonce).ok().as_deref() != Some(&nonce[..]) {
+            return Err(ProverError::NonceMismatch);
+        }
+
+        let proof = general_purpose::STANDARD
+            .decode(&parsed.proof_b64)
+            .map_err(|e| ProverError::Malformed(format!("proof_b64: {}", e)))?;
+        if proof.is_empty() {
+            return Err(ProverError::Malformed("prova vazia".into()));
+        }
+        let proof_sha256: [u8; 32] = Sha256::digest(&proof).into();
+
+        let sig = general_purpose::STANDARD
+            .decode(&parsed.signature)
+            .map_err(|e| ProverError::Malformed(format!("signature: {}", e)))?;
+        if sig.len() != SIGNBYTES {
+            return Err(ProverError::BadSignature);
+        }
+
+        let msg = transcript(&nonce, fp_bytes, &proof_sha256);
+        if !PublicKey::from_bytes(&self.cfg.public_key).verify(&msg, &sig) {
+            return Err(ProverError::BadSignature);
+        }
+
+        Ok(ProverOutput {
+            proof_b64: parsed.proof_b64,
+            proof,
+            proof_sha256,
+            nonce,
+            signature_b64: parsed.signature,
+            attempts: 0,
+        })
+    }
+}
@@ -0,0 +1,52 @@
+// =========================================================
+// 🚧 QUARENTENA — provas que o prover devolveu e não verificam
+// =========================================================
+//
+// Uma linha JSON por prova rejeitada (JSONL, append + fsync). Nada aqui
+// vai para o artifact store nem para o histórico: o arquivo só existe
+// para investigar o prover.
+
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Serialize)]
+pub struct QuarantinedProof<'a> {
+    pub urn_id: &'a str,
+    pub report: &'a serde_json::Value,
+    pub ruleset_id: &'a str,
+    pub timestamp: u64,
+    pub fp_hex: String,
+    pub proof_b64: &'a str,
+    pub prover_key_id: String,
+    pub vk_fingerprint: Option<String>,
+    pub reason: String,
+}
+
+pub struct Quarantine {
+    path: PathBuf,
+}
+
+impl Quarantine {
+    pub fn open(path: &str) -> Result<Self, String> {
+        let path = PathBuf::from(path);
+        if let Some(p) = path.parent() {
+            if !p.as_os_str().is_empty() {
+                fs::create_dir_all(p).map_err(|e| format!("falha create_dir_all({:?}): {}", p, e))?;
+            }
+        }
+        Ok(Self { path })
+    }
+
+    pub fn put(&self, entry: &QuarantinedProof<'_>) -> Result<(), String> {
+        let line = serde_json::to_string(entry).map_err(|e| format!("falha serializando quarentena: {}", e))?;
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("falha ao abrir {:?}: {}", self.path, e))?;
+        writeln!(f, "{}", line).map_err(|e| format!("falha ao escrever quarentena: {}", e))?;
+        f.sync_data().map_err(|e| format!("falha no fsync: {}", e))
+    }
+}
@@ -1,46 +1,81 @@
+// Os helpers de autorização devolvem a resposta de erro pronta do warp.
+#![allow(clippy::result_large_err)]
+
 use warp::{self, Filter, Reply};
+use warp::http::StatusCode;
+use warp::multipart::{FormData, Part};
+use futures_util::TryStreamExt;
+use bytes::Buf;
+use tokio::io::AsyncWriteExt;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH, Duration};
-use std::collections::HashMap;
 use base64::engine::{general_purpose, Engine as _};
-use reqwest;
 
 use std::env;
 
-use hmac::{Hmac, Mac};
 use sha2::{Sha256, Digest};
+use subtle::ConstantTimeEq;
 use zeroize::Zeroizing;
 use rand::RngCore;
 use pasta_curves::group::ff::PrimeField;
-use pasta_curves::pallas::Base as Fp;
-
-// Dilithium PQC
-use crystals_dilithium::dilithium2::Keypair;
 
-type HmacSha256 = Hmac<Sha256>;
-type Storage = Arc<Mutex<Vec<Proof>>>;
+mod proof_store;
+mod prover_client;
+mod quarantine;
+mod tenants;
+use proof_store::{MelQuery, ProofStore};
+use prover_client::{ProverClient, ProverConfig};
+use quarantine::{QuarantinedProof, Quarantine};
+use tenants::{LegacyPaths, Tenant, TenantError, TenantRegistry, LEGACY_TENANT_ID};
+
+use terra_dourada_core::{canonical, config, keystore};
+use terra_dourada_core::crypto::{dk_pqc_hmac, pqc_sign_hash_to_fp};
+use terra_dourada_core::report_schema::{is_mime, parse_media_sidecar, parse_report, MediaEvidenceV2, Report, SchemaErrors};
+use terra_dourada_core::media_fingerprint::{MediaFingerprint, MediaHasher};
+use terra_dourada_core::artifact_store::{router_from_config, ArtifactKind, StoreRouter};
+use terra_dourada_core::keystore::{KeyStore, RotationPolicy};
+use terra_dourada_core::signature::{sign_payload, DetachedSignature};
+use terra_dourada_core::zk_verify::{fp_from_bytes, vk_fingerprint, ZkVerifier, CIRCUIT_ID};
+use terra_dourada_core::vk_registry::{Governance, RegistryError, VkRegistry, VkStatus};
+use terra_dourada_core::config::Service;
+
+type Storage = Arc<Mutex<ProofStore>>;
+type Keys = Arc<Mutex<KeyStore>>;
+type VkReg = Arc<Mutex<VkRegistry>>;
+type Tenants = Arc<Mutex<TenantRegistry>>;
 
 // =========================================================
 // STRUCTS
 // =========================================================
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct Vote {
-    voter_id: String,
-    candidate: String,
-    #[serde(flatten)]
-    extra_fields: HashMap<String, Value>,
+/// Payload assinado / hasheado: `JCS(claim) ":" timestamp`, onde claim é
+/// o relatório tipado + `ruleset_id`. Especificação em "Canonical JSON.md".
+fn mel_payload(report: &Report, timestamp: u64) -> Result<String, canonical::CanonicalError> {
+    Ok(format!("{}:{}", canonical::canonicalize(&report.claim())?, timestamp))
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 struct Proof {
     urn_id: String,
-    vote: Vote,
+    report: Report,
+    ruleset_id: String,
     timestamp: u64,
     hash: Vec<u8>,
     proof: String,
+    cid: String,
+    /// Chave Dilithium2 que produziu a tag (ver GET /keys).
+    /// Ausente em provas anteriores ao keystore (chave efêmera).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_id: Option<String>,
+    /// VK ativa que verificou a prova (ver GET /vk/{fingerprint}).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    vk_fingerprint: Option<String>,
+    /// Assinatura destacada do payload (só com TD_PUBLISH_SIGNATURE).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<DetachedSignature>,
 }
 
 #[derive(Deserialize)]
@@ -48,55 +83,642 @@ struct RegisterVk {
     vk_b64: String,
 }
 
+#[derive(Deserialize)]
+struct ApproveVk {
+    approver_id: String,
+    version: u32,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct CreateTenant {
+    tenant_id: String,
+    #[serde(default)]
+    rate_per_minute: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct VerifyRequest {
+    proof_b64: String,
+    /// Fp público: 32 bytes little-endian em hex (o mesmo hash_hex do info_local).
+    fp_hex: String,
+    /// Opcional: VK do tenant já ativada (ex.: a de uma prova antiga,
+    /// GET /vk/{fingerprint}). Padrão: a VK ativa.
+    #[serde(default)]
+    vk_fingerprint: Option<String>,
+}
+
+fn decode_b64(s: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    general_purpose::STANDARD
+        .decode(s)
+        .or_else(|_| general_purpose::URL_SAFE_NO_PAD.decode(s))
+}
+
 // =========================================================
-// 🔥 DK-PQC-HMAC
+// 🔥 PROVAR + PUBLICAR (comum a /mel e /media)
 // =========================================================
 
-fn dk_pqc_hmac(
-    key: &[u8],
-    payload: &[u8],
-    keypair: &Keypair,
-) -> [u8; 32] {
+/// info_local (/append) e info_global (/commit), com timeout curto.
+#[derive(Clone)]
+struct Upstreams {
+    info_local_url: String,
+    info_global_url: Option<String>,
+    info_local: reqwest::Client,
+    info_global: reqwest::Client,
+}
+
+/// O que todos os tenants compartilham: artifact stores, prover e circuito.
+#[derive(Clone)]
+struct Shared {
+    stores: StoreRouter,
+    publish_signature: bool,
+    prover: ProverClient,
+    zk: Arc<ZkVerifier>,
+    upstreams: Upstreams,
+}
+
+#[derive(Clone)]
+struct MelContext {
+    storage: Storage,
+    urn_id: String,
+    hmac_key: Zeroizing<Vec<u8>>,
+    keys: Keys,
+    vk_registry: VkReg,
+    stores: StoreRouter,
+    publish_signature: bool,
+    prover: ProverClient,
+    zk: Arc<ZkVerifier>,
+    quarantine: Arc<Quarantine>,
+    upstreams: Upstreams,
+}
+
+impl MelContext {
+    fn new(shared: Shared, tenant: &Tenant) -> Self {
+        Self {
+            storage: tenant.storage.clone(),
+            urn_id: tenant.id.clone(),
+            hmac_key: tenant.hmac_key.clone(),
+            keys: tenant.keys.clone(),
+            vk_registry: tenant.vk_registry.clone(),
+            stores: shared.stores,
+            publish_signature: shared.publish_signature,
+            prover: shared.prover,
+            zk: shared.zk,
+            quarantine: tenant.quarantine.clone(),
+            upstreams: shared.upstreams,
+        }
+    }
+}
+
+/// Relatório já validado → payload canônico, HMAC, prover, artifact
+/// store e histórico. `attachments` (objeto) entra no artefato publicado.
+async fn prove_and_publish(
+    report: Report,
+    attachments: Option<Value>,
+    ctx: MelContext,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let MelContext {
+        storage, urn_id, hmac_key, keys, vk_registry, stores, publish_signature, prover, zk, quarantine, upstreams,
+    } = ctx;
+
+    let ruleset_id = report.ruleset_id().to_string();
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    // 🏛️ só verificamos com a VK ativa, lida como VK do circuito local
+    let active_vk = vk_registry.lock().unwrap().active().map(|e| e.vk_b64.clone());
+    let active_vk = match active_vk {
+        Some(vk_b64) => match decode_b64(&vk_b64) {
+            Ok(bytes) => zk.clone().load_blocking(bytes).await,
+            Err(e) => Err(format!("VK ativa inválida: {}", e)),
+        },
+        None => Err("nenhuma VK ativa (aguardando aprovação)".to_string()),
+    };
+    let active_vk = match active_vk {
+        Ok(vk) => vk,
+        Err(msg) => {
+            println!("❌ /mel recusado: {}", msg);
+            return Ok::<_, warp::Rejection>(
+                warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": msg})),
+                    StatusCode::SERVICE_UNAVAILABLE,
+                )
+                .into_response()
+            );
+        }
+    };
+    let vk_fp = active_vk.fingerprint().to_string();
+
+    // 🔑 chave ativa no instante da prova
+    let signer = match keys.lock().unwrap().signer(timestamp) {
+        Ok(s) => s,
+        Err(e) => {
+            println!("❌ Keystore sem chave ativa: {}", e);
+            return Ok::<_, warp::Rejection>(
+                warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": "keystore indisponível"})),
+                    StatusCode::SERVICE_UNAVAILABLE,
+                )
+                .into_response()
+            );
+        }
+    };
+
+    // 📐 forma canônica (JCS) — mesmo relatório, mesmos bytes
+    let payload_str = match mel_payload(&report, timestamp) {
+        Ok(p) => p,
+        Err(e) => {
+            println!("❌ Payload não canonicalizável: {}", e);
+            return Ok::<_, warp::Rejection>(
+                warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({
+                        "error": format!("payload não canonicalizável: {}", e)
+                    })),
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                )
+                .into_response()
+            );
+        }
+    };
+
+    // 🔥 tag DK-PQC-HMAC (identificador do pedido)
+    let tag_bytes = dk_pqc_hmac(
+        &hmac_key,
+        payload_str.as_bytes(),
+        signer.keypair(),
+    );
+    let tag_hex = hex::encode(tag_bytes);
+
+    // 🔥 gera o Fp para o circuito
+    let hash_fp_pqc = pqc_sign_hash_to_fp(
+        payload_str.as_bytes(),
+        signer.keypair(),
+    );
+
+    let hash_fp_bytes = hash_fp_pqc.to_repr().as_ref().to_vec();
+
+    // ✍️ modo auditável: assinatura destacada, verificável com GET /keys
+    let signature = publish_signature
+        .then(|| sign_payload(signer.keypair(), &signer.key_id, payload_str.as_bytes()));
+
+    // ==========================
+    // CHAMAR PROVER (challenge-response assinado — ver prover_client.rs)
+    // ==========================
+
+    let prover_out = match prover.prove(&tag_hex, &hash_fp_bytes, &ruleset_id, &payload_str).await {
+        Ok(out) => out,
+        Err(e) => {
+            println!("❌ Prova rejeitada: {}", e);
+            return Ok::<_, warp::Rejection>(
+                warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({
+                        "error": "erro no prover",
+                        "detail": e.to_string()
+                    })),
+                    StatusCode::BAD_GATEWAY,
+                )
+                .into_response()
+            );
+        }
+    };
+
+    println!(
+        "🟢 Prover {} assinou a prova (nonce {}, {} tentativa(s))",
+        prover.config().key_id(),
+        hex::encode(prover_out.nonce),
+        prover_out.attempts
+    );
+
+    let proof_b64 = prover_out.proof_b64.clone();
+
+    // ============================================================
+    // 🧮 VERIFICAR A PROVA (VK registrada + Fp) ANTES DE PUBLICAR
+    // ============================================================
+
+    if let Err(reason) = active_vk.verify(&prover_out.proof, hash_fp_pqc) {
+        println!("❌ Prova Halo2 NÃO verifica — quarentena: {}", reason);
+
+        let report_json = serde_json::to_value(&report).unwrap_or(Value::Null);
+        let entry = QuarantinedProof {
+            urn_id: &urn_id,
+            report: &report_json,
+            ruleset_id: &ruleset_id,
+            timestamp,
+            fp_hex: hex::encode(&hash_fp_bytes),
+            proof_b64: &proof_b64,
+            prover_key_id: prover.config().key_id(),
+            vk_fingerprint: Some(vk_fp.clone()),
+            reason: reason.clone(),
+        };
+        let quarantined = match quarantine.put(&entry) {
+            Ok(()) => true,
+            Err(e) => {
+                println!("❌ Erro ao gravar quarentena: {}", e);
+                false
+            }
+        };
+
+        return Ok::<_, warp::Rejection>(
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "error": "prova inválida",
+                    "detail": reason,
+                    "quarantined": quarantined
+                })),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            )
+            .into_response()
+        );
+    }
+
+    println!("🟢 Prova Halo2 verificada localmente (VK {})", vk_fp);
+
+    // ============================================================
+    // ✅ ADIÇÃO ÚNICA: enviar o HASH (FP PQC) pro info_local (7070)
+    // ✅ SEM HMAC (só {"hash_hex": "..."} )
+    // ✅ só depois da prova verificar: Fp de prova recusada não entra no ledger
+    // ============================================================
+    {
+        let info_local_url = format!("{}/append", upstreams.info_local_url.trim_end_matches('/'));
+        let fp_pqc_hex = hex::encode(&hash_fp_bytes);
+
+        // cliente com timeout curto (timeouts.info_local_ms) pra não travar o fluxo
+        let _ = upstreams
+            .info_local
+            .post(info_local_url)
+            .json(&serde_json::json!({ "hash_hex": fp_pqc_hex }))
+            .send()
+            .await;
+    }
+
+    // ============================================================
+    // PUBLICAR NO ARTIFACT STORE
+    // ============================================================
+
+    let mut final_json = serde_json::json!({
+        "urn_id": urn_id,
+        "report": report,
+        "ruleset_id": ruleset_id,
+        "timestamp": timestamp,
+        "key_id": signer.key_id,
+        "proof_bytes": proof_b64,
+        "prover": {
+            "key_id": prover.config().key_id(),
+            "nonce": hex::encode(prover_out.nonce),
+            "proof_sha256": hex::encode(prover_out.proof_sha256),
+            "signature": prover_out.signature_b64
+        },
+        "circuit": CIRCUIT_ID,
+        "vk_fingerprint": vk_fp
+    });
+
+    if let (Some(sig), Value::Object(obj)) = (&signature, &mut final_json) {
+        obj.insert("signature".into(), serde_json::json!(sig));
+    }
+
+    // anexos (ex.: sidecar da mídia) viajam no mesmo artefato
+    if let (Some(Value::Object(extra)), Value::Object(obj)) = (attachments, &mut final_json) {
+        obj.extend(extra);
+    }
+
+    let final_bytes = serde_json::to_vec(&final_json).unwrap();
+    let label = format!("{}_{}.json", urn_id, timestamp);
+
+    let stored = match stores.put(ArtifactKind::Report, &final_bytes, &label).await {
+        Ok(stored) => stored,
+        Err(e) => {
+            println!("❌ Erro no artifact store ({}): {}", stores.reports.name(), e);
+            return Ok::<_, warp::Rejection>(
+                warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": "falha no IPFS"})),
+                    StatusCode::BAD_GATEWAY,
+                )
+                .into_response()
+            );
+        }
+    };
+
+    println!("📦 {} OK → CID {}", stored.store, stored.id);
+
+    // ============================================================
+    // REGISTRAR NO HISTÓRICO (append-only em disco)
+    // ============================================================
+
+    let record = Proof {
+        urn_id: urn_id.clone(),
+        report: report.clone(),
+        ruleset_id: ruleset_id.clone(),
+        timestamp,
+        hash: hash_fp_bytes.clone(),
+        proof: proof_b64.clone(),
+        cid: stored.id.clone(),
+        key_id: Some(signer.key_id.clone()),
+        vk_fingerprint: Some(vk_fp.clone()),
+        signature: signature.clone(),
+    };
+
+    if let Err(e) = storage.lock().unwrap().append(record) {
+        println!("❌ Erro ao gravar histórico: {}", e);
+        return Ok::<_, warp::Rejection>(
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "error": "falha ao gravar histórico",
+                    "cid": stored.id
+                })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+            .into_response()
+        );
+    }
+
+    // 🌐 commitment para o info_global (só hash + CID), se configurado
+    if let Some(url) = &upstreams.info_global_url {
+        let commit = upstreams
+            .info_global
+            .post(format!("{}/commit", url.trim_end_matches('/')))
+            .json(&serde_json::json!({
+                "proof_hash": hex::encode(prover_out.proof_sha256),
+                "cid": stored.id,
+                "instance_id": urn_id
+            }))
+            .send()
+            .await;
+        if let Err(e) = commit {
+            println!("⚠️  info_global não recebeu o commitment: {}", e);
+        }
+    }
+
+    let mut resp = serde_json::json!({
+        "cid": stored.id,
+        "store": stored.store,
+        "size": stored.size,
+        "ruleset_id": ruleset_id,
+        "key_id": signer.key_id,
+        "timestamp": timestamp
+    });
+    if let (Some(sig), Value::Object(obj)) = (signature, &mut resp) {
+        obj.insert("signature".into(), serde_json::json!(sig));
+    }
+
+    Ok::<_, warp::Rejection>(warp::reply::json(&resp).into_response())
+}
+
+fn schema_error_reply(errs: SchemaErrors) -> warp::reply::Response {
+    println!("❌ Relatório inválido ({:?}): {} erros", errs.schema, errs.errors.len());
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({
+            "error": "relatório inválido",
+            "schema": errs.schema,
+            "errors": errs.errors
+        })),
+        StatusCode::UNPROCESSABLE_ENTITY,
+    )
+    .into_response()
+}
+
+fn bad_request(msg: impl Into<String>) -> warp::reply::Response {
+    let msg = msg.into();
+    println!("❌ {}", msg);
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "error": msg })),
+        StatusCode::BAD_REQUEST,
+    )
+    .into_response()
+}
 
-    let mut mac1 = HmacSha256::new_from_slice(key).unwrap();
-    mac1.update(payload);
-    let h1 = mac1.finalize().into_bytes();
+fn error_reply(status: StatusCode, msg: impl Into<String>) -> warp::reply::Response {
+    let msg = msg.into();
+    println!("❌ {}", msg);
+    warp::reply::with_status(warp::reply::json(&serde_json::json!({ "error": msg })), status).into_response()
+}
+
+// =========================================================
+// 🏘️ TENANT — X-Tenant-Id + Authorization: Bearer <api_key>
+// =========================================================
 
-    let signature = keypair.secret.sign(payload);
-    let h2 = Sha256::digest(&signature);
+fn bearer(auth: &Option<String>) -> Option<&str> {
+    auth.as_deref()?.strip_prefix("Bearer ").map(str::trim)
+}
 
-    let mut mix = [0u8; 32];
-    for i in 0..32 {
-        mix[i] = h1[i] ^ h2[i];
+/// Escrita (/mel, /media, /register_vk_id): API key do tenant + rate limit.
+fn authorize_tenant(
+    tenants: &Tenants,
+    tenant_id: Option<String>,
+    auth: Option<String>,
+) -> Result<Arc<Tenant>, warp::reply::Response> {
+    let Some(id) = tenant_id else {
+        return Err(error_reply(StatusCode::UNAUTHORIZED, "header X-Tenant-Id ausente"));
+    };
+    let Some(api_key) = bearer(&auth) else {
+        return Err(error_reply(StatusCode::UNAUTHORIZED, format!("tenant {}: API key ausente", id)));
+    };
+    let Some(tenant) = tenants.lock().unwrap().authenticate(&id, api_key) else {
+        return Err(error_reply(StatusCode::UNAUTHORIZED, format!("tenant {}: credenciais inválidas", id)));
+    };
+    if !tenant.allow() {
+        return Err(error_reply(StatusCode::TOO_MANY_REQUESTS, format!("tenant {}: limite de pedidos", id)));
     }
+    Ok(tenant)
+}
 
-    let mut mac_final = HmacSha256::new_from_slice(key).unwrap();
-    mac_final.update(&mix);
+/// Leitura pública (/keys, /vk, /verify, GET /mel): só o X-Tenant-Id.
+fn public_tenant(tenants: &Tenants, tenant_id: Option<String>) -> Result<Arc<Tenant>, warp::reply::Response> {
+    let Some(id) = tenant_id else {
+        return Err(error_reply(StatusCode::BAD_REQUEST, "header X-Tenant-Id ausente"));
+    };
+    tenants
+        .lock()
+        .unwrap()
+        .get(&id)
+        .ok_or_else(|| error_reply(StatusCode::NOT_FOUND, format!("tenant {} não existe", id)))
+}
 
-    mac_final.finalize().into_bytes().into()
+/// Admin API: Bearer TD_ADMIN_TOKEN (comparado por SHA-256).
+fn authorize_admin(admin_token_sha256: &Option<[u8; 32]>, auth: Option<String>) -> Result<(), warp::reply::Response> {
+    let Some(expected) = admin_token_sha256 else {
+        return Err(error_reply(StatusCode::FORBIDDEN, "admin API desabilitada (TD_ADMIN_TOKEN)"));
+    };
+    let given = Sha256::digest(bearer(&auth).unwrap_or("").as_bytes());
+    if !bool::from(given.as_slice().ct_eq(expected)) {
+        return Err(error_reply(StatusCode::UNAUTHORIZED, "token de admin inválido"));
+    }
+    Ok(())
 }
 
 // =========================================================
-// 🔥 PQC-HASH → Fp
+// 📸 POST /media — mídia + sidecar GPS/sensores (multipart)
 // =========================================================
+//
+// partes: `media` (arquivo, qualquer tamanho até TD_MEDIA_MAX_BYTES)
+//         `sidecar` (JSON: autor, captured_at, media_type?, gps?, sensores?)
+//
+// A mídia é gravada num temporário e hasheada pedaço a pedaço; o
+// fingerprint (media_sha256 + location_hash + sensor_fingerprint) entra
+// no payload canônico como media_evidence.v2 e segue o fluxo do /mel.
+
+const SIDECAR_MAX_BYTES: usize = 64 * 1024;
+
+struct MediaUpload {
+    path: PathBuf,
+    sha256: [u8; 32],
+    size: u64,
+    content_type: Option<String>,
+}
 
-fn pqc_sign_hash_to_fp(payload: &[u8], keypair: &Keypair) -> Fp {
-    let h1 = Sha256::digest(payload);
-    let signature = keypair.secret.sign(&h1);
-    let h2 = Sha256::digest(&signature);
+async fn read_part(part: Part, max: usize) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut stream = std::pin::pin!(part.stream());
+    while let Some(mut chunk) = stream.try_next().await.map_err(|e| format!("falha lendo parte: {}", e))? {
+        if out.len() + chunk.remaining() > max {
+            return Err(format!("parte maior que {} bytes", max));
+        }
+        while chunk.has_remaining() {
+            let n = chunk.chunk().len();
+            out.extend_from_slice(chunk.chunk());
+            chunk.advance(n);
+        }
+    }
+    Ok(out)
+}
+
+/// Grava a parte `media` num temporário, hasheando cada pedaço. Upload
+/// interrompido → 400; falha de disco → 500.
+async fn receive_media(part: Part, tmp_dir: &Path) -> Result<MediaUpload, warp::reply::Response> {
+    let content_type = part.content_type().map(String::from);
+
+    let mut nonce = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let path = tmp_dir.join(format!("upload_{}.part", hex::encode(nonce)));
+
+    let mut file = tokio::fs::File::create(&path)
+        .await
+        .map_err(|e| error_reply(StatusCode::INTERNAL_SERVER_ERROR, format!("falha ao criar temporário: {}", e)))?;
+    let mut hasher = MediaHasher::new();
+    let mut stream = std::pin::pin!(part.stream());
+
+    let disk = |e: std::io::Error, what: &str| error_reply(StatusCode::INTERNAL_SERVER_ERROR, format!("{}: {}", what, e));
+    let result: Result<(), warp::reply::Response> = async {
+        while let Some(mut chunk) = stream.try_next().await.map_err(|e| bad_request(format!("upload interrompido: {}", e)))? {
+            while chunk.has_remaining() {
+                let slice = chunk.chunk();
+                let n = slice.len();
+                hasher.update(slice);
+                file.write_all(slice).await.map_err(|e| disk(e, "falha gravando mídia"))?;
+                chunk.advance(n);
+            }
+        }
+        file.sync_all().await.map_err(|e| disk(e, "falha no fsync"))
+    }
+    .await;
 
-    let limbs = [
-        u64::from_le_bytes(h2[0..8].try_into().unwrap()),
-        u64::from_le_bytes(h2[8..16].try_into().unwrap()),
-        u64::from_le_bytes(h2[16..24].try_into().unwrap()),
-        u64::from_le_bytes(h2[24..32].try_into().unwrap()),
-    ];
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&path).await;
+        return Err(e);
+    }
 
-    Fp::from_raw(limbs)
+    let (sha256, size) = hasher.finish();
+    Ok(MediaUpload { path, sha256, size, content_type })
 }
 
-// PINATA
-const PINATA_JSON_ENDPOINT: &str = "https://api.pinata.cloud/pinning/pinJSONToIPFS";
+/// Lê o multipart, valida o sidecar, calcula o fingerprint e guarda a
+/// mídia. O temporário fica em `upload` para quem chamou apagar.
+async fn prepare_media(
+    mut form: FormData,
+    ctx: &MelContext,
+    tmp_dir: &Path,
+    upload: &mut Option<MediaUpload>,
+) -> Result<(Report, Value), warp::reply::Response> {
+    let mut sidecar_raw = None;
+
+    while let Some(part) = form.try_next().await.map_err(|e| bad_request(format!("multipart inválido: {}", e)))? {
+        let name = part.name().to_string();
+        match name.as_str() {
+            "sidecar" => sidecar_raw = Some(read_part(part, SIDECAR_MAX_BYTES).await.map_err(bad_request)?),
+            "media" if upload.is_none() => *upload = Some(receive_media(part, tmp_dir).await?),
+            other => return Err(bad_request(format!("parte inesperada: {}", other))),
+        }
+    }
+
+    let Some(up) = upload.as_ref() else {
+        return Err(bad_request("parte `media` ausente"));
+    };
+    let Some(raw) = sidecar_raw else {
+        return Err(bad_request("parte `sidecar` ausente"));
+    };
+    if up.size == 0 {
+        return Err(bad_request("mídia vazia"));
+    }
+
+    let value: Value = serde_json::from_slice(&raw)
+        .map_err(|e| bad_request(format!("sidecar não é JSON: {}", e)))?;
+    let sidecar = parse_media_sidecar(&value).map_err(schema_error_reply)?;
+
+    let Some(media_type) = sidecar
+        .media_type
+        .clone()
+        .or_else(|| up.content_type.clone().filter(|t| is_mime(t)))
+    else {
+        return Err(bad_request("media_type ausente (sidecar ou Content-Type da parte media)"));
+    };
+
+    // 📸 fingerprint determinístico
+    let gps = sidecar.gps.as_ref().map(|g| serde_json::to_value(g).expect("Gps serializável"));
+    let sensores = sidecar.sensores.clone().map(Value::Object);
+    let fp = MediaFingerprint::compute(&up.sha256, up.size, gps.as_ref(), sensores.as_ref())
+        .map_err(|e| bad_request(format!("sidecar não canonicalizável: {}", e)))?;
+
+    println!("📸 Mídia {} bytes → sha256 {} · fingerprint {}", fp.media_size, fp.media_sha256, fp.fingerprint);
+
+    let label = format!("media_{}", fp.media_sha256);
+    let stored = ctx.stores.media.put_file(&up.path, &up.sha256, &label).await.map_err(|e| {
+        println!("❌ Erro no artifact store de mídia ({}): {}", ctx.stores.media.name(), e);
+        warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": "falha ao guardar mídia"})),
+            StatusCode::BAD_GATEWAY,
+        )
+        .into_response()
+    })?;
+
+    println!("📦 {} guardou mídia → {}", stored.store, stored.id);
+
+    let report = Report::MediaUpload(MediaEvidenceV2 {
+        autor: sidecar.autor.clone(),
+        media_sha256: fp.media_sha256,
+        media_size: fp.media_size,
+        media_type,
+        media_cid: stored.id,
+        captured_at: sidecar.captured_at,
+        location_hash: fp.location_hash,
+        sensor_fingerprint: fp.sensor_fingerprint,
+        fingerprint: fp.fingerprint,
+        descricao: sidecar.descricao.clone(),
+    });
+
+    // o sidecar vai junto no artefato: é dele que o auditor recalcula os hashes
+    Ok((report, serde_json::json!({ "sidecar": sidecar })))
+}
+
+async fn handle_media(
+    form: FormData,
+    ctx: MelContext,
+    tmp_dir: PathBuf,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let mut upload = None;
+    let prepared = prepare_media(form, &ctx, &tmp_dir, &mut upload).await;
+
+    // a cópia definitiva já está no artifact store
+    if let Some(up) = upload {
+        let _ = tokio::fs::remove_file(&up.path).await;
+    }
+
+    match prepared {
+        Ok((report, attachments)) => prove_and_publish(report, Some(attachments), ctx).await,
+        Err(reply) => Ok(reply),
+    }
+}
 
 // =========================================================
 // MAIN BACKEND
@@ -104,31 +726,178 @@ const PINATA_JSON_ENDPOINT: &str = "https://api.pinata.cloud/pinning/pinJSONToIP
 
 #[tokio::main]
 async fn main() {
-    let storage: Storage = Arc::new(Mutex::new(Vec::new()));
-    let vk_storage: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    // ⚙️ terra-dourada.toml + TD_* + flags (ver config.rs)
+    let cfg = config::load(Service::Server);
+
+    // 🤝 Prover: identidade Dilithium2 + timeouts/retries
+    let prover = ProverClient::new(
+        ProverConfig::from_env(&cfg.upstream, cfg.timeouts.prover())
+            .unwrap_or_else(|e| panic!("❌ Config do prover inválida: {}", e)),
+    )
+    .unwrap_or_else(|e| panic!("❌ {}", e));
+
+    println!(
+        "🤝 Prover {} · chave {} · timeout {:?} · {} retries",
+        prover.config().url,
+        prover.config().key_id(),
+        prover.config().timeout,
+        prover.config().retries
+    );
+
+    // Artifact stores ([storage] reports / media)
+    let stores = router_from_config(&cfg.storage, cfg.timeouts.store())
+        .unwrap_or_else(|e| panic!("❌ Artifact store inválido: {}", e));
+
+    println!("📦 Reports → {} · Mídia → {}", stores.reports.name(), stores.media.name());
+
+    let media_tmp_dir = PathBuf::from(&cfg.media.tmp_dir);
+    std::fs::create_dir_all(&media_tmp_dir)
+        .expect("❌ Falha ao criar diretório temporário de mídia");
+
+    let media_max_bytes = cfg.media.max_bytes;
+
+    // 🔑 Keystores Dilithium2 dos tenants (cifrados em disco, com rotação)
+    let passphrase = Zeroizing::new(
+        env::var("TD_KEYSTORE_PASSPHRASE")
+            .expect("❌ TD_KEYSTORE_PASSPHRASE não definida")
+            .into_bytes(),
+    );
+    // a passphrase não fica no ambiente do processo
+    env::remove_var("TD_KEYSTORE_PASSPHRASE");
+
+    let policy = RotationPolicy {
+        interval: cfg.keys.rotation_secs,
+        overlap: cfg.keys.overlap_secs,
+    };
+
+    // ✍️ assinatura destacada no /mel e no artefato (auditores)
+    let publish_signature = cfg.keys.publish_signature;
+    if publish_signature {
+        println!("✍️ Assinatura Dilithium2 destacada publicada em cada prova");
+    }
 
-    let urn_id = "TERRADOURADA_URN_01".to_string();
+    // 🧮 Verificador Halo2 local (circuito td.fp.v1)
+    let zk = Arc::new(ZkVerifier::new(cfg.zk.k).unwrap_or_else(|e| panic!("❌ {}", e)));
+
+    println!(
+        "🧮 Circuito {} · k={} · VK esperada {}",
+        CIRCUIT_ID,
+        zk.k(),
+        vk_fingerprint(zk.vk_bytes())
+    );
+
+    // 🏛️ Governança M-de-N das VKs (a mesma para todos os tenants)
+    let approvers_path = PathBuf::from(&cfg.zk.vk_approvers);
+    let governance = Governance::load(&approvers_path)
+        .unwrap_or_else(|e| panic!("❌ Aprovadores de VK inválidos: {}", e));
+    if governance.approvers.is_empty() {
+        println!("⚠️  {:?} ausente — nenhuma VK poderá ser ativada", approvers_path);
+    }
+    println!(
+        "🏛️ Governança de VKs: {} de {} aprovações",
+        governance.threshold,
+        governance.approvers.len()
+    );
+
+    // 🏘️ Tenants: cada um com HMAC, chaves, VK, histórico e rate limit próprios
+    let tenants_dir = PathBuf::from(&cfg.tenants.dir);
+    let default_rate = cfg.tenants.rate_per_min;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let mut registry = TenantRegistry::open(&tenants_dir, passphrase, policy, &approvers_path, default_rate, now)
+        .unwrap_or_else(|e| panic!("❌ Falha ao abrir tenants: {}", e));
+
+    // 📦 arquivos do modo single-tenant → tenant TERRADOURADA_URN_01
+    match registry.adopt_legacy(&LegacyPaths::from_config(&cfg.tenants), now) {
+        Ok(Some(api_key)) => {
+            println!("📦 Dados single-tenant migrados para o tenant {}", LEGACY_TENANT_ID);
+            println!("🔑 API key de {} (só aparece agora): {}", LEGACY_TENANT_ID, api_key.as_str());
+        }
+        Ok(None) => {}
+        Err(e) => panic!("❌ Falha ao migrar dados single-tenant: {}", e),
+    }
 
-    // 🔥 Gerar chave HMAC 256 bits aleatória
-    let mut hmac_bytes = [0u8; 32];
-    rand::thread_rng().fill_bytes(&mut hmac_bytes);
-    let hmac_key = Zeroizing::new(hmac_bytes.to_vec());
+    println!("🏘️ Tenants {:?}: {}", tenants_dir, registry.records().len());
+    for tenant in registry.tenants() {
+        let keys = tenant.keys.lock().unwrap();
+        let vk = tenant.vk_registry.lock().unwrap();
+        println!(
+            "   • {} · chave ativa {} · {} provas · VK ativa {}",
+            tenant.id,
+            keys.active_key_id(now).unwrap_or("—"),
+            tenant.storage.lock().unwrap().len(),
+            vk.active().map(|e| e.fingerprint.as_str()).unwrap_or("—")
+        );
+    }
 
-    println!("🔐 HMAC gerada (servidor → prover)");
+    let tenants: Tenants = Arc::new(Mutex::new(registry));
 
-    // PINATA JWT
-    let pinata_jwt = env::var("PINATA_JWT")
-        .expect("❌ Variável PINATA_JWT ausente!");
+    // 🛡️ Admin API (criação de tenants): desabilitada sem TD_ADMIN_TOKEN
+    let admin_token_sha256: Option<[u8; 32]> = env::var("TD_ADMIN_TOKEN")
+        .ok()
+        .filter(|t| !t.is_empty())
+        .map(|t| Sha256::digest(t.as_bytes()).into());
+    env::remove_var("TD_ADMIN_TOKEN");
+    if admin_token_sha256.is_none() {
+        println!("⚠️  TD_ADMIN_TOKEN ausente — admin API desabilitada");
+    }
 
-    // PQC keypair
-    let pqc_keypair = Arc::new(Keypair::generate(None));
+    // ⏱️ rotação agendada: gera a sucessora ao entrar no overlap
+    {
+        let tenants = tenants.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                tick.tick().await;
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+                let all = tenants.lock().unwrap().tenants();
+                for tenant in all {
+                    match tenant.keys.lock().unwrap().rotate(now) {
+                        Ok(created) => {
+                            for id in created {
+                                println!("🔑 [{}] Nova chave Dilithium2 publicada: {}", tenant.id, id);
+                            }
+                        }
+                        Err(e) => println!("❌ [{}] Falha na rotação de chaves: {}", tenant.id, e),
+                    }
+                }
+            }
+        });
+    }
 
-    let hmac_filter = warp::any().map(move || hmac_key.clone());
-    let pqc_filter = warp::any().map(move || pqc_keypair.clone());
-    let storage_filter = warp::any().map(move || storage.clone());
-    let vk_filter = warp::any().map(move || vk_storage.clone());
-    let urn_filter = warp::any().map(move || urn_id.clone());
-    let jwt_filter = warp::any().map(move || pinata_jwt.clone());
+    let upstreams = Upstreams {
+        info_local_url: cfg.upstream.info_local_url.clone(),
+        info_global_url: cfg.upstream.info_global_url.clone(),
+        info_local: reqwest::Client::builder()
+            .timeout(cfg.timeouts.info_local())
+            .build()
+            .expect("❌ falha ao criar cliente HTTP"),
+        info_global: reqwest::Client::builder()
+            .timeout(cfg.timeouts.info_global())
+            .build()
+            .expect("❌ falha ao criar cliente HTTP"),
+    };
+
+    println!(
+        "🧠 info_local {} · 🌐 info_global {}",
+        upstreams.info_local_url,
+        upstreams.info_global_url.as_deref().unwrap_or("— (sem commitment global)")
+    );
+
+    let shared = Shared {
+        stores,
+        publish_signature,
+        prover,
+        zk: zk.clone(),
+        upstreams,
+    };
+
+    let tenants_filter = warp::any().map(move || tenants.clone());
+    let shared_filter = warp::any().map(move || shared.clone());
+    let zk_filter = warp::any().map(move || zk.clone());
+    let admin_filter = warp::any().map(move || admin_token_sha256);
+    let tenant_id_header = warp::header::optional::<String>("x-tenant-id");
+    let auth_header = warp::header::optional::<String>("authorization");
 
     // =============================================================
     // 🔥 POST /mel — fluxo principal
@@ -136,252 +905,466 @@ async fn main() {
 
     let mel_post_route = warp::post()
         .and(warp::path("mel"))
+        .and(tenant_id_header)
+        .and(auth_header)
         .and(warp::body::json())
-        .and(storage_filter.clone())
-        .and(urn_filter.clone())
-        .and(hmac_filter.clone())
-        .and(pqc_filter.clone())
-        .and(vk_filter.clone())
-        .and(jwt_filter.clone())
-        .and_then(
-            |value: Value,
-             storage: Storage,
-             urn_id: String,
-             hmac_key: Zeroizing<Vec<u8>>,
-             pqc_keypair: Arc<Keypair>,
-             vk_store: Arc<Mutex<Option<String>>>,
-             jwt: String| async move {
-
-                println!("📥 [/mel] JSON recebido: {}", value);
-
-                let voter_id = value.get("autor")
-                    .or(value.get("cid_autor"))
-                    .or(value.get("usuario"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("anonimo")
-                    .to_string();
-
-                let candidate = value.get("candidato")
-                    .or(value.get("voto"))
-                    .or(value.get("produto"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("desconhecido")
-                    .to_string();
-
-                let vote = Vote {
-                    voter_id,
-                    candidate,
-                    extra_fields: serde_json::from_value(value.clone()).unwrap(),
-                };
-
-                let timestamp = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-
-                let vote_json = serde_json::to_string(&vote).unwrap();
-
-                let payload_str = format!("{}:{}", vote_json, timestamp);
-
-                // 🔥 gera a HMAC que deve voltar
-                let tag_bytes = dk_pqc_hmac(
-                    &hmac_key,
-                    payload_str.as_bytes(),
-                    &pqc_keypair,
-                );
-                let tag_hex = hex::encode(tag_bytes);
-
-                // 🔥 gera o Fp para o circuito
-                let hash_fp_pqc = pqc_sign_hash_to_fp(
-                    payload_str.as_bytes(),
-                    &pqc_keypair,
-                );
-
-                let hash_fp_bytes = hash_fp_pqc.to_repr().as_ref().to_vec();
-
-                // ============================================================
-                // ✅ ADIÇÃO ÚNICA: enviar o HASH (FP PQC) pro info_local (7070)
-                // ✅ SEM HMAC (só {"hash_hex": "..."} )
-                // ============================================================
-                {
-                    let info_local_url = "http://127.0.0.1:7070/append";
-                    let fp_pqc_hex = hex::encode(&hash_fp_bytes);
-
-                    // cliente com timeout curto pra não travar o fluxo
-                    let client_7070 = reqwest::Client::builder()
-                        .timeout(Duration::from_secs(2))
-                        .build()
-                        .unwrap();
-
-                    let _ = client_7070
-                        .post(info_local_url)
-                        .json(&serde_json::json!({ "hash_hex": fp_pqc_hex }))
-                        .send()
-                        .await;
-                }
+        .and(tenants_filter.clone())
+        .and(shared_filter.clone())
+        .and_then(|tenant_id: Option<String>, auth: Option<String>, value: Value, tenants: Tenants, shared: Shared| async move {
 
-                // ==========================
-                // CHAMAR PROVER
-                // ==========================
+            let tenant = match authorize_tenant(&tenants, tenant_id, auth) {
+                Ok(t) => t,
+                Err(reply) => return Ok::<_, warp::Rejection>(reply),
+            };
 
-                let prover_url = "http://127.0.0.1:8081/prove";
+            println!("📥 [/mel · {}] JSON recebido: {}", tenant.id, value);
 
-                let payload_json = serde_json::json!({
-                    "fp_bytes": hash_fp_bytes,
-                    "hmac": tag_hex,        // <- enviar a HMAC ao prover
-                    "original": payload_str // <- opcional
-                });
+            // 🧾 schema tipado → erros por campo (422)
+            let report = match parse_report(&value) {
+                Ok(r) => r,
+                Err(errs) => return Ok::<_, warp::Rejection>(schema_error_reply(errs)),
+            };
 
-                let client = reqwest::Client::new();
-                let resp = client.post(prover_url).json(&payload_json).send().await;
+            prove_and_publish(report, None, MelContext::new(shared, &tenant)).await
+        });
 
-                let (proof_b64, returned_hmac) = match resp {
-                    Ok(r) => {
-                        let body = r.text().await.unwrap_or_default();
-                        println!("📥 Prover -> {}", body);
+    // =============================================================
+    // 📸 POST /media — evidência de mídia (multipart)
+    // =============================================================
 
-                        let json: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+    let media_route = warp::post()
+        .and(warp::path("media"))
+        .and(tenant_id_header)
+        .and(auth_header)
+        .and(warp::multipart::form().max_length(media_max_bytes + SIDECAR_MAX_BYTES as u64))
+        .and(tenants_filter.clone())
+        .and(shared_filter.clone())
+        .and(warp::any().map(move || media_tmp_dir.clone()))
+        .and_then(|tenant_id: Option<String>, auth: Option<String>, form: FormData, tenants: Tenants, shared: Shared, tmp_dir: PathBuf| async move {
+            // o multipart só é lido depois da autenticação
+            match authorize_tenant(&tenants, tenant_id, auth) {
+                Ok(tenant) => handle_media(form, MelContext::new(shared, &tenant), tmp_dir).await,
+                Err(reply) => Ok(reply),
+            }
+        });
 
-                        let proof = json.get("proof_b64")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
+    // =============================================================
+    // VK register (proposta — ativação só com aprovações, ver /vk)
+    // =============================================================
 
-                        let hmac_ret = json.get("hmac")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
+    let register_vk_route = warp::post()
+        .and(warp::path("register_vk_id"))
+        .and(tenant_id_header)
+        .and(auth_header)
+        .and(warp::body::json::<RegisterVk>())
+        .and(tenants_filter.clone())
+        .and(zk_filter.clone())
+        .and_then(|tenant_id: Option<String>, auth: Option<String>, body: RegisterVk, tenants: Tenants, zk: Arc<ZkVerifier>| async move {
 
-                        (proof, hmac_ret)
-                    }
-                    Err(e) => {
-                        println!("❌ Erro ao chamar prover: {:?}", e);
-                        return Ok::<_, warp::Rejection>(
-                            warp::reply::json(&serde_json::json!({"error": "erro no prover"}))
-                                .into_response()
-                        );
-                    }
-                };
+            let tenant = match authorize_tenant(&tenants, tenant_id, auth) {
+                Ok(t) => t,
+                Err(reply) => return Ok::<_, warp::Rejection>(reply),
+            };
 
-                // ============================================================
-                // 🔥 VALIDAR HMAC —— segurança soberana
-                // ============================================================
+            println!("📥 [/register_vk_id · {}] VK recebida!", tenant.id);
 
-                if returned_hmac.clone().unwrap_or_default() != tag_hex {
-                    println!("❌ HMAC NÃO CONFERE — Prova rejeitada!");
+            let decoded = match decode_b64(&body.vk_b64) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    println!("❌ VK inválida: {:?}", e);
                     return Ok::<_, warp::Rejection>(
                         warp::reply::json(&serde_json::json!({
-                            "error": "HMAC inválida"
+                            "success": false,
+                            "message": format!("VK inválida: {:?}", e)
                         }))
                         .into_response()
                     );
                 }
+            };
 
-                println!("🟢 HMAC CONFERE — prova é realmente resposta do pedido!");
-
-                // ============================================================
-                // Se não tem prova → rejeita
-                // ============================================================
-
-                let Some(proof_b64) = proof_b64 else {
+            // bytes, não texto: a VK tem que ser a do circuito que verificamos
+            let vk = match zk.load_blocking(decoded.clone()).await {
+                Ok(vk) => vk,
+                Err(e) => {
+                    println!("❌ {}", e);
                     return Ok::<_, warp::Rejection>(
-                        warp::reply::json(&serde_json::json!({"error": "prova ausente"}))
-                            .into_response()
+                        warp::reply::with_status(
+                            warp::reply::json(&serde_json::json!({
+                                "success": false,
+                                "message": e
+                            })),
+                            StatusCode::UNPROCESSABLE_ENTITY,
+                        )
+                        .into_response()
                     );
-                };
-
-                // ============================================================
-                // PUBLICAR NO IPFS
-                // ============================================================
-
-                let final_json = serde_json::json!({
-                    "urn_id": urn_id,
-                    "vote": vote,
-                    "timestamp": timestamp,
-                    "proof_bytes": proof_b64,
-                    "verifying_key": vk_store.lock().unwrap().clone()
-                });
-
-                let client_pin = reqwest::Client::new();
-
-                if let Ok(resp_pin) = client_pin
-                    .post(PINATA_JSON_ENDPOINT)
-                    .header("Authorization", jwt)
-                    .json(&final_json)
-                    .send()
-                    .await
-                {
-                    if let Ok(txt) = resp_pin.text().await {
-                        if let Ok(json) = serde_json::from_str::<Value>(&txt) {
-                            println!("📦 Pinata OK → CID retornado");
-
-                            return Ok::<_, warp::Rejection>(
-                                warp::reply::json(&json).into_response()
-                            );
-                        }
-                    }
                 }
+            };
 
-                Ok::<_, warp::Rejection>(
-                    warp::reply::json(&serde_json::json!({"error": "falha no IPFS"}))
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let entry = match tenant.vk_registry.lock().unwrap().propose(&decoded, vk.circuit(), vk.k(), now) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    println!("❌ Falha ao registrar VK: {}", e);
+                    return Ok::<_, warp::Rejection>(
+                        warp::reply::with_status(
+                            warp::reply::json(&serde_json::json!({"success": false, "message": e})),
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
                         .into_response()
-                )
-            });
-
-    // =============================================================
-    // VK register
-    // =============================================================
-
-    let register_vk_route = warp::post()
-        .and(warp::path("register_vk_id"))
-        .and(warp::body::json::<RegisterVk>())
-        .and(vk_filter.clone())
-        .and_then(|body: RegisterVk, vk_store: Arc<Mutex<Option<String>>>| async move {
-
-            println!("📥 [/register_vk_id] VK recebida!");
-
-            let decoded = match general_purpose::STANDARD.decode(&body.vk_b64) {
-                Ok(bytes) => bytes,
-                Err(_) => match general_purpose::URL_SAFE_NO_PAD.decode(&body.vk_b64) {
-                    Ok(bytes2) => bytes2,
-                    Err(e) => {
-                        println!("❌ VK inválida: {:?}", e);
-                        return Ok::<_, warp::Rejection>(
-                            warp::reply::json(&serde_json::json!({
-                                "success": false,
-                                "message": format!("VK inválida: {:?}", e)
-                            }))
-                            .into_response()
-                        );
-                    }
+                    );
                 }
             };
 
-            let text = String::from_utf8_lossy(&decoded).to_string();
-            println!("🔑 VK decodificada e armazenada!");
-
-            *vk_store.lock().unwrap() = Some(text);
+            println!("🔑 [{}] VK {} v{} → {:?}", tenant.id, entry.fingerprint, entry.version, entry.status);
 
             Ok::<_, warp::Rejection>(
                 warp::reply::json(&serde_json::json!({
                     "success": true,
-                    "message": "VK registrada"
+                    "message": "VK registrada",
+                    "tenant_id": tenant.id,
+                    "vk_fingerprint": entry.fingerprint,
+                    "version": entry.version,
+                    "status": entry.status
                 }))
                 .into_response()
             )
         });
 
+    // =============================================================
+    // 🏛️ GET /vk · GET /vk/{fingerprint} · POST /vk/{fingerprint}/approve
+    // =============================================================
+
+    let vk_list_route = warp::get()
+        .and(warp::path("vk"))
+        .and(warp::path::end())
+        .and(tenant_id_header)
+        .and(tenants_filter.clone())
+        .map(|tenant_id: Option<String>, tenants: Tenants| {
+            let tenant = match public_tenant(&tenants, tenant_id) {
+                Ok(t) => t,
+                Err(reply) => return reply,
+            };
+            let reg = tenant.vk_registry.lock().unwrap();
+            let entries: Vec<Value> = reg
+                .entries()
+                .iter()
+                .map(|e| {
+                    serde_json::json!({
+                        "fingerprint": e.fingerprint,
+                        "version": e.version,
+                        "circuit": e.circuit,
+                        "k": e.k,
+                        "status": e.status,
+                        "proposed_at": e.proposed_at,
+                        "activated_at": e.activated_at,
+                        "retired_at": e.retired_at,
+                        "approvals": e.approvals.len()
+                    })
+                })
+                .collect();
+            let gov = reg.governance();
+            warp::reply::json(&serde_json::json!({
+                "tenant_id": tenant.id,
+                "active": reg.active().map(|e| &e.fingerprint),
+                "threshold": gov.threshold,
+                "approvers": gov.approvers.iter()
+                    .map(|a| serde_json::json!({"id": a.id, "role": a.role}))
+                    .collect::<Vec<_>>(),
+                "entries": entries
+            }))
+            .into_response()
+        });
+
+    let vk_get_route = warp::get()
+        .and(warp::path!("vk" / String))
+        .and(tenant_id_header)
+        .and(tenants_filter.clone())
+        .map(|fingerprint: String, tenant_id: Option<String>, tenants: Tenants| {
+            let tenant = match public_tenant(&tenants, tenant_id) {
+                Ok(t) => t,
+                Err(reply) => return reply,
+            };
+            let reg = tenant.vk_registry.lock().unwrap();
+            match reg.get(&fingerprint) {
+                Some(entry) => warp::reply::json(entry).into_response(),
+                None => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({"error": "VK não registrada"})),
+                    StatusCode::NOT_FOUND,
+                )
+                .into_response(),
+            }
+        });
+
+    // a assinatura do aprovador é a credencial; o tenant entra na mensagem assinada
+    let vk_approve_route = warp::post()
+        .and(warp::path!("vk" / String / "approve"))
+        .and(tenant_id_header)
+        .and(warp::body::json::<ApproveVk>())
+        .and(tenants_filter.clone())
+        .map(|fingerprint: String, tenant_id: Option<String>, body: ApproveVk, tenants: Tenants| {
+            let tenant = match public_tenant(&tenants, tenant_id) {
+                Ok(t) => t,
+                Err(reply) => return reply,
+            };
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let result = tenant.vk_registry.lock().unwrap().approve(
+                &fingerprint,
+                body.version,
+                &body.approver_id,
+                &body.signature,
+                now,
+            );
+
+            match result {
+                Ok(entry) => {
+                    println!(
+                        "🏛️ [{}] VK {} v{}: aprovação de {} ({} aprovações) → {:?}",
+                        tenant.id, entry.fingerprint, entry.version, body.approver_id, entry.approvals.len(), entry.status
+                    );
+                    warp::reply::json(&serde_json::json!({
+                        "tenant_id": tenant.id,
+                        "fingerprint": entry.fingerprint,
+                        "version": entry.version,
+                        "status": entry.status,
+                        "approvals": entry.approvals.len()
+                    }))
+                    .into_response()
+                }
+                Err(e) => {
+                    println!("❌ Aprovação de VK recusada: {}", e);
+                    let status = match e {
+                        RegistryError::NotFound(_) => StatusCode::NOT_FOUND,
+                        RegistryError::UnknownApprover(_) | RegistryError::BadSignature(_) => StatusCode::FORBIDDEN,
+                        RegistryError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                        _ => StatusCode::CONFLICT,
+                    };
+                    warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                        status,
+                    )
+                    .into_response()
+                }
+            }
+        });
+
+    // =============================================================
+    // 🧮 POST /verify — verificação de prova para terceiros
+    // =============================================================
+
+    let verify_route = warp::post()
+        .and(warp::path("verify"))
+        .and(warp::path::end())
+        .and(tenant_id_header)
+        .and(warp::body::json::<VerifyRequest>())
+        .and(tenants_filter.clone())
+        .and(zk_filter.clone())
+        .and_then(|tenant_id: Option<String>, body: VerifyRequest, tenants: Tenants, zk: Arc<ZkVerifier>| async move {
+            let fp = match hex::decode(&body.fp_hex).ok().as_deref().and_then(fp_from_bytes) {
+                Some(fp) => fp,
+                None => return Ok::<_, warp::Rejection>(bad_request("fp_hex deve ter 32 bytes (little-endian) de um elemento do corpo")),
+            };
+            let proof = match decode_b64(&body.proof_b64) {
+                Ok(p) => p,
+                Err(e) => return Ok(bad_request(format!("proof_b64 inválido: {}", e))),
+            };
+
+            // só VKs do registro do tenant (keygen é caro: nada de VK vinda do pedido)
+            let tenant = match public_tenant(&tenants, tenant_id) {
+                Ok(t) => t,
+                Err(reply) => return Ok(reply),
+            };
+            let vk_b64 = {
+                let registry = tenant.vk_registry.lock().unwrap();
+                match &body.vk_fingerprint {
+                    Some(fp) => registry
+                        .get(fp)
+                        .filter(|e| e.status != VkStatus::Proposed)
+                        .map(|e| e.vk_b64.clone()),
+                    None => registry.active().map(|e| e.vk_b64.clone()),
+                }
+            };
+            let Some(bytes) = vk_b64.and_then(|b64| decode_b64(&b64).ok()) else {
+                return Ok(match &body.vk_fingerprint {
+                    Some(fp) => error_reply(StatusCode::NOT_FOUND, format!("VK {} não foi ativada neste tenant", fp)),
+                    None => error_reply(StatusCode::SERVICE_UNAVAILABLE, "nenhuma VK ativa para este circuito"),
+                });
+            };
+
+            let checked = tokio::task::spawn_blocking(move || {
+                let vk = zk.load(&bytes)?;
+                let result = vk.verify(&proof, fp);
+                Ok::<_, String>((vk, result))
+            })
+            .await
+            .map_err(|e| format!("verificação abortada: {}", e))
+            .and_then(|r| r);
+
+            let (vk, result) = match checked {
+                Ok(r) => r,
+                Err(e) => return Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e)),
+            };
+            println!("🧮 [/verify] {} → {}", body.fp_hex, if result.is_ok() { "válida" } else { "inválida" });
+
+            Ok(warp::reply::json(&serde_json::json!({
+                "valid": result.is_ok(),
+                "circuit": CIRCUIT_ID,
+                "k": vk.k(),
+                "vk_fingerprint": vk.fingerprint(),
+                "error": result.err()
+            }))
+            .into_response())
+        });
+
+    // GET /mel?offset=&limit=&from=&to=&autor=&candidato=&schema=  (X-Tenant-Id)
     let mel_get_route = warp::get()
         .and(warp::path("mel"))
-        .and(storage_filter.clone())
-        .and_then(|storage: Storage| async move {
+        .and(tenant_id_header)
+        .and(warp::query::<MelQuery>())
+        .and(tenants_filter.clone())
+        .and_then(|tenant_id: Option<String>, query: MelQuery, tenants: Tenants| async move {
+            let tenant = match public_tenant(&tenants, tenant_id) {
+                Ok(t) => t,
+                Err(reply) => return Ok::<_, warp::Rejection>(reply),
+            };
+            let store = tenant.storage.lock().unwrap();
             Ok::<_, warp::Rejection>(
-                warp::reply::json(&*storage.lock().unwrap())
+                warp::reply::json(&store.query(&query)).into_response()
             )
         });
 
+    // =============================================================
+    // 🔑 GET /keys — chaves públicas Dilithium2 (atual + histórico)
+    // =============================================================
+
+    let keys_route = warp::get()
+        .and(warp::path("keys"))
+        .and(warp::path::end())
+        .and(tenant_id_header)
+        .and(tenants_filter.clone())
+        .map(|tenant_id: Option<String>, tenants: Tenants| {
+            let tenant = match public_tenant(&tenants, tenant_id) {
+                Ok(t) => t,
+                Err(reply) => return reply,
+            };
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let keys = tenant.keys.lock().unwrap();
+            warp::reply::json(&serde_json::json!({
+                "tenant_id": tenant.id,
+                "algorithm": keystore::ALGORITHM,
+                "active": keys.active_key_id(now),
+                "keys": keys.public_keys(now)
+            }))
+            .into_response()
+        });
+
+    // =============================================================
+    // 🛡️ ADMIN — POST/GET /admin/tenants · POST /admin/tenants/{id}/api_key
+    // =============================================================
+
+    let admin_create_route = warp::post()
+        .and(warp::path!("admin" / "tenants"))
+        .and(auth_header)
+        .and(warp::body::json::<CreateTenant>())
+        .and(admin_filter)
+        .and(tenants_filter.clone())
+        .map(|auth: Option<String>, body: CreateTenant, admin: Option<[u8; 32]>, tenants: Tenants| {
+            if let Err(reply) = authorize_admin(&admin, auth) {
+                return reply;
+            }
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let created = TenantRegistry::create_shared(&tenants, &body.tenant_id, body.rate_per_minute, now);
+
+            match created {
+                Ok((tenant, api_key)) => {
+                    let key_id = tenant.keys.lock().unwrap().active_key_id(now).map(String::from);
+                    println!("🏘️ Tenant {} criado · chave ativa {}", tenant.id, key_id.as_deref().unwrap_or("—"));
+                    warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({
+                            "tenant_id": tenant.id,
+                            "api_key": api_key.as_str(),
+                            "key_id": key_id
+                        })),
+                        StatusCode::CREATED,
+                    )
+                    .into_response()
+                }
+                Err(e) => {
+                    let status = match e {
+                        TenantError::InvalidId(_) => StatusCode::BAD_REQUEST,
+                        TenantError::Exists(_) => StatusCode::CONFLICT,
+                        TenantError::NotFound(_) => StatusCode::NOT_FOUND,
+                        TenantError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                    };
+                    error_reply(status, e.to_string())
+                }
+            }
+        });
+
+    let admin_list_route = warp::get()
+        .and(warp::path!("admin" / "tenants"))
+        .and(auth_header)
+        .and(admin_filter)
+        .and(tenants_filter.clone())
+        .map(|auth: Option<String>, admin: Option<[u8; 32]>, tenants: Tenants| {
+            if let Err(reply) = authorize_admin(&admin, auth) {
+                return reply;
+            }
+            let reg = tenants.lock().unwrap();
+            let list: Vec<Value> = reg
+                .records()
+                .iter()
+                .map(|r| {
+                    serde_json::json!({
+                        "tenant_id": r.tenant_id,
+                        "rate_per_minute": r.rate_per_minute,
+                        "created_at": r.created_at
+                    })
+                })
+                .collect();
+            warp::reply::json(&serde_json::json!({ "tenants": list })).into_response()
+        });
+
+    let admin_rotate_route = warp::post()
+        .and(warp::path!("admin" / "tenants" / String / "api_key"))
+        .and(auth_header)
+        .and(admin_filter)
+        .and(tenants_filter.clone())
+        .map(|tenant_id: String, auth: Option<String>, admin: Option<[u8; 32]>, tenants: Tenants| {
+            if let Err(reply) = authorize_admin(&admin, auth) {
+                return reply;
+            }
+            match tenants.lock().unwrap().rotate_api_key(&tenant_id) {
+                Ok(api_key) => {
+                    println!("🏘️ Tenant {}: API key trocada", tenant_id);
+                    warp::reply::json(&serde_json::json!({
+                        "tenant_id": tenant_id,
+                        "api_key": api_key.as_str()
+                    }))
+                    .into_response()
+                }
+                Err(e @ TenantError::NotFound(_)) => error_reply(StatusCode::NOT_FOUND, e.to_string()),
+                Err(e) => error_reply(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            }
+        });
+
     let routes = mel_post_route
+        .or(media_route)
         .or(mel_get_route)
-        .or(register_vk_route);
-
-    println!("🚀 Backend Terra Dourada rodando em http://127.0.0.1:8080");
-    warp::serve(routes).run(([127, 0, 0, 1], 8080)).await;
+        .or(keys_route)
+        .or(verify_route)
+        .or(vk_list_route)
+        .or(vk_get_route)
+        .or(vk_approve_route)
+        .or(register_vk_route)
+        .or(admin_create_route)
+        .or(admin_list_route)
+        .or(admin_rotate_route);
+
+    let listen = &cfg.server;
+    println!("🚀 Backend Terra Dourada rodando em {}", listen.url());
+
+    match &listen.tls {
+        Some(tls) => warp::serve(routes).tls().cert_path(&tls.cert).key_path(&tls.key).run(listen.addr()).await,
+        None => warp::serve(routes).run(listen.addr()).await,
+    }
 }
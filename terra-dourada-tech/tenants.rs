@@ -0,0 +1,525 @@
+// =========================================================
+// 🏘️ TENANTS — vários urns (fazendas, missões, devices) num gateway
+// =========================================================
+//
+// {TD_TENANTS_DIR}/tenants.json            cadastro: id, SHA-256 da API key, rate
+// {TD_TENANTS_DIR}/{id}/keys/              keystore Dilithium2 do tenant
+// {TD_TENANTS_DIR}/{id}/mel_proofs.jsonl   histórico
+// {TD_TENANTS_DIR}/{id}/vk_registry.json   VKs (aprovadores de TD_VK_APPROVERS)
+// {TD_TENANTS_DIR}/{id}/quarantine.jsonl   provas rejeitadas
+//
+// O tenant_id é o urn_id das provas. Cada tenant tem HMAC, chaves PQC,
+// VK, histórico e limite de pedidos próprios; nada é compartilhado além
+// do prover, do circuito e dos artifact stores.
+//
+// A API key só aparece na resposta de criação/rotação. Em disco fica o
+// SHA-256 dela, e a comparação é feita entre digests, em tempo constante.
+// A chave HMAC fica no keystore do tenant, cifrada como as Dilithium2.
+//
+// Criar um tenant roda Argon2 + keygen Dilithium2: `create_shared` faz
+// isso fora do lock do registro (o id fica reservado enquanto isso).
+//
+// Migração do modo single-tenant: os arquivos de antes dos tenants
+// (TD_PROOF_STORE_PATH, TD_KEYSTORE_DIR, TD_QUARANTINE_PATH,
+// TD_VK_REGISTRY_PATH) são movidos para {TD_TENANTS_DIR}/TERRADOURADA_URN_01/
+// na primeira subida, e o urn antigo vira um tenant com API key nova.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+use terra_dourada_core::config::TenantsConfig;
+use terra_dourada_core::keystore::{KeyStore, RotationPolicy};
+use terra_dourada_core::vk_registry::{Governance, VkRegistry};
+
+use crate::proof_store::ProofStore;
+use crate::quarantine::Quarantine;
+
+const TENANTS_FILE: &str = "tenants.json";
+const MAX_ID_LEN: usize = 64;
+
+/// O urn fixo do gateway antes dos tenants.
+pub const LEGACY_TENANT_ID: &str = "TERRADOURADA_URN_01";
+
+// =========================================================
+// CADASTRO
+// =========================================================
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TenantRecord {
+    pub tenant_id: String,
+    api_key_sha256: String,
+    pub rate_per_minute: u32,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct TenantsFile {
+    tenants: Vec<TenantRecord>,
+}
+
+#[derive(Debug)]
+pub enum TenantError {
+    InvalidId(String),
+    Exists(String),
+    NotFound(String),
+    Io(String),
+}
+
+impl fmt::Display for TenantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TenantError::InvalidId(id) => {
+                write!(f, "tenant_id {:?} inválido (use [A-Za-z0-9_-], até {} caracteres)", id, MAX_ID_LEN)
+            }
+            TenantError::Exists(id) => write!(f, "tenant {} já existe", id),
+            TenantError::NotFound(id) => write!(f, "tenant {} não existe", id),
+            TenantError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// O id vira nome de diretório: nada de `/`, `..` ou espaço.
+pub fn valid_tenant_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn new_api_key() -> Zeroizing<String> {
+    let mut raw = Zeroizing::new([0u8; 32]);
+    rand::thread_rng().fill_bytes(raw.as_mut());
+    Zeroizing::new(format!("td_{}", hex::encode(raw.as_ref())))
+}
+
+// =========================================================
+// RATE LIMIT (token bucket por tenant)
+// =========================================================
+
+struct RateLimiter {
+    per_minute: u32,
+    tokens: f64,
+    last: std::time::Instant,
+}
+
+impl RateLimiter {
+    fn new(per_minute: u32) -> Self {
+        Self { per_minute, tokens: per_minute as f64, last: std::time::Instant::now() }
+    }
+
+    fn allow(&mut self) -> bool {
+        let now = std::time::Instant::now();
+        let refill = now.duration_since(self.last).as_secs_f64() * self.per_minute as f64 / 60.0;
+        self.tokens = (self.tokens + refill).min(self.per_minute as f64);
+        self.last = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// =========================================================
+// TENANT
+// =========================================================
+
+pub struct Tenant {
+    pub id: String,
+    pub hmac_key: Zeroizing<Vec<u8>>,
+    pub keys: Arc<Mutex<KeyStore>>,
+    pub vk_registry: Arc<Mutex<VkRegistry>>,
+    pub storage: Arc<Mutex<ProofStore>>,
+    pub quarantine: Arc<Quarantine>,
+    limiter: Mutex<RateLimiter>,
+}
+
+impl Tenant {
+    /// Consome um pedido do balde; false → 429.
+    pub fn allow(&self) -> bool {
+        self.limiter.lock().unwrap().allow()
+    }
+}
+
+// =========================================================
+// REGISTRO DE TENANTS
+// =========================================================
+
+/// O necessário para abrir um tenant, separado do cadastro para rodar
+/// sem o lock do registro.
+struct TenantLoader {
+    dir: PathBuf,
+    /// Mantida só para abrir o keystore de tenants criados em runtime.
+    passphrase: Zeroizing<Vec<u8>>,
+    policy: RotationPolicy,
+    approvers_path: PathBuf,
+    default_rate: u32,
+}
+
+impl TenantLoader {
+    fn load(&self, rec: &TenantRecord, now: u64) -> Result<Tenant, String> {
+        let base = self.dir.join(&rec.tenant_id);
+        let path = |name: &str| base.join(name).to_string_lossy().into_owned();
+
+        let mut keys = KeyStore::open(&base.join("keys"), &self.passphrase, self.policy, now)?;
+        let hmac_key = keys.hmac_key()?;
+        let storage = ProofStore::open(&path("mel_proofs.jsonl"))?;
+        let governance = Governance::load(&self.approvers_path)?;
+        let vk_registry = VkRegistry::open(&path("vk_registry.json"), &rec.tenant_id, governance)?;
+        let quarantine = Quarantine::open(&path("quarantine.jsonl"))?;
+
+        Ok(Tenant {
+            id: rec.tenant_id.clone(),
+            hmac_key,
+            keys: Arc::new(Mutex::new(keys)),
+            vk_registry: Arc::new(Mutex::new(vk_registry)),
+            storage: Arc::new(Mutex::new(storage)),
+            quarantine: Arc::new(quarantine),
+            limiter: Mutex::new(RateLimiter::new(rec.rate_per_minute)),
+        })
+    }
+
+    /// Registro e tenant novos, com a API key. Não toca no cadastro.
+    fn prepare(
+        &self,
+        id: &str,
+        rate_per_minute: Option<u32>,
+        now: u64,
+    ) -> Result<(TenantRecord, Tenant, Zeroizing<String>), TenantError> {
+        let api_key = new_api_key();
+        let rec = TenantRecord {
+            tenant_id: id.into(),
+            api_key_sha256: sha256_hex(api_key.as_bytes()),
+            rate_per_minute: rate_per_minute.filter(|r| *r > 0).unwrap_or(self.default_rate),
+            created_at: now,
+        };
+        let tenant = self.load(&rec, now).map_err(TenantError::Io)?;
+        Ok((rec, tenant, api_key))
+    }
+}
+
+pub struct TenantRegistry {
+    file: TenantsFile,
+    tenants: HashMap<String, Arc<Tenant>>,
+    /// ids em criação fora do lock (ver create_shared)
+    creating: HashSet<String>,
+    loader: Arc<TenantLoader>,
+}
+
+impl TenantRegistry {
+    /// Abre o cadastro e todos os tenants (keystore, histórico, VKs).
+    pub fn open(
+        dir: &Path,
+        passphrase: Zeroizing<Vec<u8>>,
+        policy: RotationPolicy,
+        approvers_path: &Path,
+        default_rate: u32,
+        now: u64,
+    ) -> Result<Self, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("falha create_dir_all({:?}): {}", dir, e))?;
+        let path = dir.join(TENANTS_FILE);
+
+        let file: TenantsFile = if path.exists() {
+            let txt = fs::read_to_string(&path).map_err(|e| format!("falha ao ler {:?}: {}", path, e))?;
+            serde_json::from_str(&txt).map_err(|e| format!("cadastro de tenants corrompido {:?}: {}", path, e))?
+        } else {
+            TenantsFile::default()
+        };
+
+        let mut reg = Self {
+            file,
+            tenants: HashMap::new(),
+            creating: HashSet::new(),
+            loader: Arc::new(TenantLoader {
+                dir: dir.to_path_buf(),
+                passphrase,
+                policy,
+                approvers_path: approvers_path.to_path_buf(),
+                default_rate,
+            }),
+        };
+
+        for rec in reg.file.tenants.clone() {
+            if !valid_tenant_id(&rec.tenant_id) {
+                return Err(TenantError::InvalidId(rec.tenant_id).to_string());
+            }
+            let tenant = reg.loader.load(&rec, now).map_err(|e| format!("tenant {}: {}", rec.tenant_id, e))?;
+            reg.tenants.insert(rec.tenant_id.clone(), Arc::new(tenant));
+        }
+
+        Ok(reg)
+    }
+
+    fn persist(&self) -> Result<(), String> {
+        let path = self.loader.dir.join(TENANTS_FILE);
+        let tmp = path.with_extension("json.tmp");
+        let bytes = serde_json::to_vec_pretty(&self.file)
+            .map_err(|e| format!("falha serializando cadastro de tenants: {}", e))?;
+
+        let mut opts = fs::OpenOptions::new();
+        opts.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.mode(0o600);
+        }
+        let mut f: File = opts.open(&tmp).map_err(|e| format!("falha ao criar {:?}: {}", tmp, e))?;
+        f.write_all(&bytes).map_err(|e| format!("falha ao escrever cadastro de tenants: {}", e))?;
+        f.sync_all().map_err(|e| format!("falha no fsync: {}", e))?;
+        fs::rename(&tmp, &path).map_err(|e| format!("falha ao renomear cadastro de tenants: {}", e))
+    }
+
+    pub fn records(&self) -> &[TenantRecord] {
+        &self.file.tenants
+    }
+
+    pub fn tenants(&self) -> Vec<Arc<Tenant>> {
+        self.tenants.values().cloned().collect()
+    }
+
+    /// Tenant para leituras públicas (chaves, VKs, verificação).
+    pub fn get(&self, id: &str) -> Option<Arc<Tenant>> {
+        self.tenants.get(id).cloned()
+    }
+
+    /// Tenant cuja API key confere (digest contra digest, tempo constante).
+    pub fn authenticate(&self, id: &str, api_key: &str) -> Option<Arc<Tenant>> {
+        let rec = self.file.tenants.iter().find(|r| r.tenant_id == id)?;
+        let expected = hex::decode(&rec.api_key_sha256).ok()?;
+        let given = Sha256::digest(api_key.as_bytes());
+        if !bool::from(given.as_slice().ct_eq(&expected)) {
+            return None;
+        }
+        self.get(id)
+    }
+
+    /// Cria o tenant (diretório, keystore, registro de VKs) e devolve a
+    /// API key — a única vez em que ela existe fora do cliente.
+    pub fn create(
+        &mut self,
+        id: &str,
+        rate_per_minute: Option<u32>,
+        now: u64,
+    ) -> Result<(Arc<Tenant>, Zeroizing<String>), TenantError> {
+        self.reserve(id)?;
+        let prepared = self.loader.prepare(id, rate_per_minute, now);
+        self.creating.remove(id);
+        let (rec, tenant, api_key) = prepared?;
+        Ok((self.commit(rec, tenant)?, api_key))
+    }
+
+    /// Como `create`, mas com o registro compartilhado: o lock só é
+    /// segurado para reservar o id e para gravar o cadastro, não durante
+    /// o Argon2 e o keygen.
+    pub fn create_shared(
+        registry: &Mutex<Self>,
+        id: &str,
+        rate_per_minute: Option<u32>,
+        now: u64,
+    ) -> Result<(Arc<Tenant>, Zeroizing<String>), TenantError> {
+        let loader = {
+            let mut reg = registry.lock().unwrap();
+            reg.reserve(id)?;
+            reg.loader.clone()
+        };
+
+        let prepared = loader.prepare(id, rate_per_minute, now);
+
+        let mut reg = registry.lock().unwrap();
+        reg.creating.remove(id);
+        let (rec, tenant, api_key) = prepared?;
+        Ok((reg.commit(rec, tenant)?, api_key))
+    }
+
+    fn reserve(&mut self, id: &str) -> Result<(), TenantError> {
+        if !valid_tenant_id(id) {
+            return Err(TenantError::InvalidId(id.into()));
+        }
+        if self.tenants.contains_key(id) || !self.creating.insert(id.into()) {
+            return Err(TenantError::Exists(id.into()));
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self, rec: TenantRecord, tenant: Tenant) -> Result<Arc<Tenant>, TenantError> {
+        let id = rec.tenant_id.clone();
+        self.file.tenants.push(rec);
+        if let Err(e) = self.persist() {
+            self.file.tenants.pop();
+            return Err(TenantError::Io(e));
+        }
+        let tenant = Arc::new(tenant);
+        self.tenants.insert(id, tenant.clone());
+        Ok(tenant)
+    }
+
+    /// Troca a API key; a anterior deixa de valer na hora.
+    pub fn rotate_api_key(&mut self, id: &str) -> Result<Zeroizing<String>, TenantError> {
+        let idx = self
+            .file
+            .tenants
+            .iter()
+            .position(|r| r.tenant_id == id)
+            .ok_or_else(|| TenantError::NotFound(id.into()))?;
+
+        let api_key = new_api_key();
+        let old = std::mem::replace(&mut self.file.tenants[idx].api_key_sha256, sha256_hex(api_key.as_bytes()));
+        if let Err(e) = self.persist() {
+            self.file.tenants[idx].api_key_sha256 = old;
+            return Err(TenantError::Io(e));
+        }
+        Ok(api_key)
+    }
+
+    /// Adota os arquivos do modo single-tenant como o tenant
+    /// LEGACY_TENANT_ID. Move o que ainda estiver no lugar antigo (uma
+    /// migração interrompida é retomada) e cadastra o tenant; devolve a
+    /// API key nova, ou None se não havia nada a migrar.
+    pub fn adopt_legacy(&mut self, legacy: &LegacyPaths, now: u64) -> Result<Option<Zeroizing<String>>, TenantError> {
+        let base = self.loader.dir.join(LEGACY_TENANT_ID);
+        let moves = legacy.moves(&base);
+
+        if self.tenants.contains_key(LEGACY_TENANT_ID) {
+            for (from, _) in moves.iter().filter(|(from, _)| from.exists()) {
+                println!("⚠️  {:?} não migrado: o tenant {} já existe", from, LEGACY_TENANT_ID);
+            }
+            return Ok(None);
+        }
+        if !base.exists() && !moves.iter().any(|(from, _)| from.exists()) {
+            return Ok(None);
+        }
+
+        fs::create_dir_all(&base).map_err(|e| TenantError::Io(format!("falha create_dir_all({:?}): {}", base, e)))?;
+        for (from, to) in &moves {
+            if !from.exists() {
+                continue;
+            }
+            if to.exists() {
+                return Err(TenantError::Io(format!("{:?} e {:?} existem; mova um deles à mão", from, to)));
+            }
+            fs::rename(from, to).map_err(|e| TenantError::Io(format!("falha ao mover {:?} → {:?}: {}", from, to, e)))?;
+            println!("📦 {:?} → {:?}", from, to);
+        }
+
+        let (_, api_key) = self.create(LEGACY_TENANT_ID, None, now)?;
+        Ok(Some(api_key))
+    }
+}
+
+// =========================================================
+// ARQUIVOS DO MODO SINGLE-TENANT
+// =========================================================
+
+/// Onde o gateway guardava o urn único antes dos tenants.
+pub struct LegacyPaths {
+    pub proof_store: PathBuf,
+    pub keystore_dir: PathBuf,
+    pub quarantine: PathBuf,
+    pub vk_registry: PathBuf,
+}
+
+impl LegacyPaths {
+    /// tenants.legacy_* (config.rs), com os env vars e defaults de antes.
+    pub fn from_config(cfg: &TenantsConfig) -> Self {
+        Self {
+            proof_store: PathBuf::from(&cfg.legacy_proof_store),
+            keystore_dir: PathBuf::from(&cfg.legacy_keystore_dir),
+            quarantine: PathBuf::from(&cfg.legacy_quarantine),
+            vk_registry: PathBuf::from(&cfg.legacy_vk_registry),
+        }
+    }
+
+    /// (origem, destino no diretório do tenant), nos nomes do load().
+    fn moves(&self, base: &Path) -> Vec<(PathBuf, PathBuf)> {
+        vec![
+            (self.keystore_dir.clone(), base.join("keys")),
+            (self.proof_store.clone(), base.join("mel_proofs.jsonl")),
+            (self.quarantine.clone(), base.join("quarantine.jsonl")),
+            (self.vk_registry.clone(), base.join("vk_registry.json")),
+        ]
+    }
+}
+
+// =========================================================
+// TESTES
+// =========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn dir(nome: &str) -> PathBuf {
+        let mut p = std::env::temp_dir();
+        p.push(format!("tenants_{}_{}", std::process::id(), nome));
+        let _ = fs::remove_dir_all(&p);
+        fs::create_dir_all(&p).unwrap();
+        p
+    }
+
+    fn abrir(dir: &Path) -> TenantRegistry {
+        let policy = RotationPolicy { interval: 86_400, overlap: 3_600 };
+        let passphrase = Zeroizing::new(b"teste".to_vec());
+        TenantRegistry::open(&dir.join("tenants"), passphrase, policy, &dir.join("approvers.json"), 60, 1_000).unwrap()
+    }
+
+    #[test]
+    fn single_tenant_vira_tenant_legado() {
+        let d = dir("legado");
+        let legacy = LegacyPaths {
+            proof_store: d.join("mel_proofs.jsonl"),
+            keystore_dir: d.join("keys"),
+            quarantine: d.join("quarantine.jsonl"),
+            vk_registry: d.join("vk_registry.json"),
+        };
+        fs::write(&legacy.proof_store, "").unwrap();
+        fs::write(&legacy.quarantine, "").unwrap();
+
+        let mut reg = abrir(&d);
+        let api_key = reg.adopt_legacy(&legacy, 1_000).unwrap().expect("nada migrado");
+        assert!(!legacy.proof_store.exists());
+        assert!(d.join("tenants").join(LEGACY_TENANT_ID).join("mel_proofs.jsonl").exists());
+        assert!(reg.authenticate(LEGACY_TENANT_ID, &api_key).is_some());
+        assert!(reg.authenticate(LEGACY_TENANT_ID, "td_errada").is_none());
+
+        // segunda subida: já migrado
+        let mut reg = abrir(&d);
+        assert!(reg.adopt_legacy(&legacy, 1_000).unwrap().is_none());
+        assert!(reg.authenticate(LEGACY_TENANT_ID, &api_key).is_some());
+        let _ = fs::remove_dir_all(&d);
+    }
+
+    #[test]
+    fn hmac_do_tenant_sobrevive_ao_restart() {
+        let d = dir("hmac");
+        let reg = Mutex::new(abrir(&d));
+        let (tenant, _) = TenantRegistry::create_shared(&reg, "fazenda-1", None, 1_000).unwrap();
+        let hmac = tenant.hmac_key.clone();
+
+        // mesmo id em criação ou criado → Exists
+        assert!(matches!(
+            TenantRegistry::create_shared(&reg, "fazenda-1", None, 1_000),
+            Err(TenantError::Exists(_))
+        ));
+        reg.lock().unwrap().creating.insert("fazenda-2".into());
+        assert!(matches!(reg.lock().unwrap().create("fazenda-2", None, 1_000), Err(TenantError::Exists(_))));
+
+        let reg = abrir(&d);
+        assert_eq!(reg.get("fazenda-1").unwrap().hmac_key, hmac);
+        let _ = fs::remove_dir_all(&d);
+    }
+}
@@ -0,0 +1,117 @@
+// =========================================================
+// 📸 VERIFY MEDIA — recalcula o fingerprint de uma mídia (offline)
+// =========================================================
+//
+// uso: verify_media <midia> <relatorio.json>
+//
+// <midia> é o arquivo baixado (pelo media_cid do relatório).
+// <relatorio.json> é o artefato publicado pelo POST /media:
+//   { "report": { "schema": "media_evidence.v2", ... }, "sidecar": { ... } }
+//
+// Recalcula media_sha256 (streaming), location_hash, sensor_fingerprint
+// e o fingerprint final, e compara campo a campo com o relatório.
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+use std::{env, fs, process};
+
+use terra_dourada_core::media_fingerprint::{hash_file, MediaFingerprint};
+
+#[derive(Deserialize)]
+struct Artifact {
+    report: MediaReport,
+    sidecar: Sidecar,
+}
+
+#[derive(Deserialize)]
+struct MediaReport {
+    schema: String,
+    media_sha256: String,
+    media_size: u64,
+    media_cid: Option<String>,
+    location_hash: Option<String>,
+    sensor_fingerprint: Option<String>,
+    fingerprint: String,
+}
+
+#[derive(Deserialize)]
+struct Sidecar {
+    gps: Option<Value>,
+    sensores: Option<Value>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 3 {
+        eprintln!("uso: verify_media <midia> <relatorio.json>");
+        process::exit(2);
+    }
+
+    let txt = fs::read_to_string(&args[2]).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao ler {}: {}", args[2], e);
+        process::exit(2);
+    });
+
+    let artifact: Artifact = serde_json::from_str(&txt).unwrap_or_else(|e| {
+        eprintln!("❌ relatório inválido: {}", e);
+        process::exit(2);
+    });
+
+    if artifact.report.schema != "media_evidence.v2" {
+        eprintln!("❌ schema {} não tem fingerprint de mídia", artifact.report.schema);
+        process::exit(2);
+    }
+
+    let (sha256, size) = hash_file(Path::new(&args[1])).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao ler {}: {}", args[1], e);
+        process::exit(2);
+    });
+
+    let calc = MediaFingerprint::compute(
+        &sha256,
+        size,
+        artifact.sidecar.gps.as_ref(),
+        artifact.sidecar.sensores.as_ref(),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("❌ sidecar não canonicalizável: {}", e);
+        process::exit(2);
+    });
+
+    let r = &artifact.report;
+    if let Some(cid) = &r.media_cid {
+        println!("📦 media_cid = {}", cid);
+    }
+
+    let checks = [
+        ("media_sha256", Some(calc.media_sha256.clone()), Some(r.media_sha256.clone())),
+        ("media_size", Some(calc.media_size.to_string()), Some(r.media_size.to_string())),
+        ("location_hash", calc.location_hash.clone(), r.location_hash.clone()),
+        ("sensor_fingerprint", calc.sensor_fingerprint.clone(), r.sensor_fingerprint.clone()),
+        ("fingerprint", Some(calc.fingerprint.clone()), Some(r.fingerprint.clone())),
+    ];
+
+    let mut ok = true;
+    for (campo, calculado, registrado) in checks {
+        if calculado == registrado {
+            println!("✅ {} = {}", campo, calculado.as_deref().unwrap_or("—"));
+        } else {
+            ok = false;
+            println!(
+                "❌ {}: calculado {} ≠ registrado {}",
+                campo,
+                calculado.as_deref().unwrap_or("—"),
+                registrado.as_deref().unwrap_or("—")
+            );
+        }
+    }
+
+    if !ok {
+        eprintln!("❌ Mídia NÃO corresponde ao relatório");
+        process::exit(1);
+    }
+
+    println!("✅ Fingerprint confere — mídia íntegra");
+}
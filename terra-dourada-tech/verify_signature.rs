@@ -0,0 +1,91 @@
+// =========================================================
+// ✍️ VERIFY SIGNATURE — confere a origem de um artefato (offline)
+// =========================================================
+//
+// uso: verify_signature <artefato.json> <keys.json>
+//
+// <artefato.json> é o JSON publicado pelo /mel ou /media com assinatura:
+//   { "report": {...}, "ruleset_id": "...", "timestamp": ...,
+//     "signature": { "algorithm": "dilithium2", "key_id": "...", "value": "..." } }
+// <keys.json> é a resposta salva do GET /keys.
+//
+// Reconstrói payload = JCS(report + ruleset_id) ":" timestamp, acha a
+// chave pelo key_id, confere a janela de validade e a assinatura.
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::{env, fs, process};
+
+use terra_dourada_core::canonical;
+use terra_dourada_core::signature::{verify_published, DetachedSignature, PublishedKey};
+
+#[derive(Deserialize)]
+struct Artifact {
+    report: Value,
+    ruleset_id: String,
+    timestamp: u64,
+    signature: Option<DetachedSignature>,
+}
+
+#[derive(Deserialize)]
+struct Keys {
+    keys: Vec<PublishedKey>,
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &str) -> T {
+    let txt = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao ler {}: {}", path, e);
+        process::exit(2);
+    });
+    serde_json::from_str(&txt).unwrap_or_else(|e| {
+        eprintln!("❌ {} inválido: {}", path, e);
+        process::exit(2);
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 3 {
+        eprintln!("uso: verify_signature <artefato.json> <keys.json>");
+        process::exit(2);
+    }
+
+    let artifact: Artifact = read_json(&args[1]);
+    let keys: Keys = read_json(&args[2]);
+
+    let Some(sig) = artifact.signature else {
+        eprintln!("❌ artefato sem assinatura destacada (TD_PUBLISH_SIGNATURE desligado?)");
+        process::exit(2);
+    };
+
+    let Some(key) = keys.keys.iter().find(|k| k.key_id == sig.key_id) else {
+        eprintln!("❌ key_id {} não está publicado em {}", sig.key_id, args[2]);
+        process::exit(1);
+    };
+
+    let mut claim = artifact.report;
+    let Value::Object(obj) = &mut claim else {
+        eprintln!("❌ report não é um objeto");
+        process::exit(2);
+    };
+    obj.insert("ruleset_id".into(), Value::String(artifact.ruleset_id));
+
+    let payload = match canonical::canonicalize(&claim) {
+        Ok(c) => format!("{}:{}", c, artifact.timestamp),
+        Err(e) => {
+            eprintln!("❌ report não canonicalizável: {}", e);
+            process::exit(2);
+        }
+    };
+
+    println!("🔑 key_id = {} [{}, {})", key.key_id, key.valid_from, key.valid_until);
+
+    match verify_published(key, payload.as_bytes(), artifact.timestamp, &sig) {
+        Ok(()) => println!("✅ Assinatura Dilithium2 confere — origem verificada"),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    }
+}
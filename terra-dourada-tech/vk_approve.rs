@@ -0,0 +1,93 @@
+// =========================================================
+// 🏛️ VK APPROVE — chave de aprovador e assinatura de ativação
+// =========================================================
+//
+// uso: vk_approve keygen <aprovador.key>
+//      vk_approve sign <aprovador.key> <approver_id> <tenant_id> <fingerprint> <version>
+//
+// keygen grava o par Dilithium2 (base64, modo 0600) e imprime a chave
+// pública para o arquivo TD_VK_APPROVERS.
+// sign imprime o corpo pronto para POST /vk/<fingerprint>/approve
+// (com X-Tenant-Id: <tenant_id>). Confira fingerprint e versão em
+// GET /vk/<fingerprint> do mesmo tenant antes de assinar.
+
+use base64::engine::{general_purpose, Engine as _};
+use crystals_dilithium::dilithium2::{Keypair, KEYPAIRBYTES};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::{env, fs, process};
+use terra_dourada_core::vk_registry::approval_message;
+use zeroize::Zeroizing;
+
+fn usage() -> ! {
+    eprintln!("uso: vk_approve keygen <aprovador.key>");
+    eprintln!("     vk_approve sign <aprovador.key> <approver_id> <tenant_id> <fingerprint> <version>");
+    process::exit(2);
+}
+
+fn keygen(path: &str) {
+    let keypair = Keypair::generate(None);
+    let encoded = Zeroizing::new(general_purpose::STANDARD.encode(keypair.to_bytes()));
+
+    let mut opts = OpenOptions::new();
+    opts.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    let mut f = opts.open(path).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao criar {}: {}", path, e);
+        process::exit(2);
+    });
+    f.write_all(encoded.as_bytes()).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao gravar {}: {}", path, e);
+        process::exit(2);
+    });
+
+    println!("🔑 Chave de aprovador gravada em {}", path);
+    println!("{}", general_purpose::STANDARD.encode(keypair.public.to_bytes()));
+}
+
+fn sign(path: &str, approver_id: &str, tenant_id: &str, fingerprint: &str, version: &str) {
+    let version: u32 = version.parse().unwrap_or_else(|_| usage());
+    if fingerprint.len() != 64 || !fingerprint.bytes().all(|b| b.is_ascii_hexdigit()) {
+        eprintln!("❌ fingerprint deve ser SHA-256 em hex");
+        process::exit(2);
+    }
+
+    let encoded = Zeroizing::new(fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao ler {}: {}", path, e);
+        process::exit(2);
+    }));
+    let bytes = Zeroizing::new(general_purpose::STANDARD.decode(encoded.trim()).unwrap_or_else(|e| {
+        eprintln!("❌ chave inválida: {}", e);
+        process::exit(2);
+    }));
+    if bytes.len() != KEYPAIRBYTES {
+        eprintln!("❌ chave com {} bytes (esperado {})", bytes.len(), KEYPAIRBYTES);
+        process::exit(2);
+    }
+
+    let keypair = Keypair::from_bytes(&bytes);
+    let msg = approval_message(tenant_id, &fingerprint.to_ascii_lowercase(), version);
+    let sig = keypair.sign(&msg);
+
+    println!(
+        "{}",
+        serde_json::json!({
+            "approver_id": approver_id,
+            "version": version,
+            "signature": general_purpose::STANDARD.encode(sig)
+        })
+    );
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("keygen") if args.len() == 3 => keygen(&args[2]),
+        Some("sign") if args.len() == 7 => sign(&args[2], &args[3], &args[4], &args[5], &args[6]),
+        _ => usage(),
+    }
+}
@@ -0,0 +1,224 @@
+// =========================================================
+// 🧠 MIND CHECK — reprodutibilidade do mind.bin
+// =========================================================
+//
+// uso: mind_check [mind_vectors.json]
+//      mind_check --ledger <ledger>
+//
+// Vetores: para cada caso, grava `lines` num arquivo de treino e gera o
+// mind.bin (relógio do ledger) cinco vezes — dois treinos completos, um
+// incremental de uma vez, um incremental salvo no meio do caminho e um
+// recarregado do mind.bin do meio (carregar_mind_bin) e retomado. Os cinco
+// têm de ser idênticos byte a byte e bater com `mind_sha256`.
+//
+// v1: cada `file` (mind.bin v1, relativo ao JSON) é migrado para v2 com
+// migrar_mind_bin e o resultado tem de bater com `mind_sha256`.
+//
+// --ledger: reconstrói a mente de um ledger real duas vezes, compara os
+// bytes e imprime o fp_hex, que deve ser o do GET /state do serviço.
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use terra_dourada_core::fxl_turbo::treino::{executar_treino_completo, migrar_mind_bin, TreinoConfig, TreinoIncremental};
+use terra_dourada_core::ledger::{fp_from_mind_and_version, read_file, verify_ledger};
+
+#[derive(Deserialize)]
+struct Vectors {
+    cases: Vec<Case>,
+    #[serde(default)]
+    v1: Vec<V1Case>,
+}
+
+#[derive(Deserialize)]
+struct Case {
+    name: String,
+    lines: Vec<String>,
+    mind_sha256: String,
+}
+
+#[derive(Deserialize)]
+struct V1Case {
+    name: String,
+    file: String,
+    mind_sha256: String,
+}
+
+fn tmp(nome: &str) -> String {
+    let mut p: PathBuf = env::temp_dir();
+    p.push(format!("mind_check_{}_{}", process::id(), nome));
+    p.to_string_lossy().into_owned()
+}
+
+/// Mesmo config dos serviços info_local / info_global.
+fn config(input: &str, mind: &str) -> TreinoConfig {
+    let mut cfg = TreinoConfig::ledger(input.to_string());
+    cfg.caminho_mind_bin = Some(mind.to_string());
+    cfg.salvar_resultados = false;
+    cfg.caminho_resultados = None;
+    cfg
+}
+
+fn ler(path: &str) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("falha ao ler {}: {}", path, e))
+}
+
+/// Gera o mind.bin de `lines` pelos cinco caminhos; devolve os bytes se
+/// todos forem idênticos.
+fn gerar(lines: &[&str]) -> Result<Vec<u8>, String> {
+    let input = tmp("input.txt");
+    let mut texto = lines.join("\n");
+    texto.push('\n');
+    fs::write(&input, texto).map_err(|e| format!("falha ao gravar {}: {}", input, e))?;
+
+    let mut saidas = Vec::new();
+
+    for rodada in ["completo_1", "completo_2"] {
+        let mind = tmp(rodada);
+        executar_treino_completo(config(&input, &mind)).map_err(|e| format!("{}: {}", rodada, e))?;
+        saidas.push((rodada, ler(&mind)?));
+        let _ = fs::remove_file(&mind);
+    }
+
+    let mind = tmp("incremental");
+    TreinoIncremental::a_partir_de_linhas(config(&input, &mind), lines.iter().copied()).salvar()?;
+    saidas.push(("incremental", ler(&mind)?));
+
+    // salva no meio (como o serviço faz a cada append) e continua; o mesmo
+    // mind.bin do meio é recarregado, sem o texto original, e continua também
+    let meio = lines.len() / 2;
+    let mut treino = TreinoIncremental::a_partir_de_linhas(config(&input, &mind), lines[..meio].iter().copied());
+    treino.salvar()?;
+
+    let mut carregado = TreinoIncremental::carregar(config(&input, &mind)).map_err(|e| format!("carregar: {}", e))?;
+    let (a, b) = (treino.analise(), carregado.analise());
+    if a.context_history != b.context_history || a.hashes_unicos != b.hashes_unicos || a.ultima_linha != b.ultima_linha {
+        return Err("estado restaurado do mind.bin diverge do original".into());
+    }
+
+    for linha in &lines[meio..] {
+        treino.incorporar(linha);
+        carregado.incorporar(linha);
+    }
+    treino.salvar()?;
+    saidas.push(("incremental_retomado", ler(&mind)?));
+    carregado.salvar()?;
+    saidas.push(("carregado_retomado", ler(&mind)?));
+    let _ = fs::remove_file(&mind);
+    let _ = fs::remove_file(&input);
+
+    let (_, base) = &saidas[0];
+    for (nome, bytes) in &saidas[1..] {
+        if bytes != base {
+            return Err(format!("{} ≠ completo_1 ({} vs {} bytes)", nome, bytes.len(), base.len()));
+        }
+    }
+    Ok(saidas.swap_remove(0).1)
+}
+
+/// v1 → v2 num arquivo temporário; devolve os bytes v2.
+fn migrar(v1: &str) -> Result<Vec<u8>, String> {
+    let destino = tmp("migrado");
+    let _ = fs::remove_file(&destino);
+    migrar_mind_bin(v1, &destino, &config(v1, &destino))?;
+    let bytes = ler(&destino)?;
+    let _ = fs::remove_file(&destino);
+    Ok(bytes)
+}
+
+fn check_ledger(path: &str) {
+    let texto = read_file(path);
+    let head = verify_ledger(&texto).unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        process::exit(1);
+    });
+
+    let lines: Vec<&str> = texto.lines().collect();
+    let bytes = gerar(&lines).unwrap_or_else(|e| {
+        eprintln!("❌ mind.bin não reprodutível: {}", e);
+        process::exit(1);
+    });
+
+    let mind = tmp("ledger");
+    fs::write(&mind, &bytes).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao gravar {}: {}", mind, e);
+        process::exit(2);
+    });
+    let fp_hex = fp_from_mind_and_version(&mind, head.version);
+    let _ = fs::remove_file(&mind);
+
+    println!("✅ mind.bin reprodutível · {} bytes · sha256 {}", bytes.len(), hex::encode(Sha256::digest(&bytes)));
+    println!("🧬 v={} · fp_hex {}", head.version, fp_hex);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.get(1).map(String::as_str) == Some("--ledger") {
+        match args.get(2) {
+            Some(path) => check_ledger(path),
+            None => {
+                eprintln!("uso: mind_check --ledger <ledger>");
+                process::exit(2);
+            }
+        }
+        return;
+    }
+
+    let path = args.get(1).cloned().unwrap_or_else(|| "mind_vectors.json".into());
+
+    let txt = fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao ler {}: {}", path, e);
+        process::exit(2);
+    });
+
+    let vectors: Vectors = serde_json::from_str(&txt).unwrap_or_else(|e| {
+        eprintln!("❌ vetores inválidos: {}", e);
+        process::exit(2);
+    });
+
+    let mut resultados = Vec::new();
+
+    for case in &vectors.cases {
+        let lines: Vec<&str> = case.lines.iter().map(String::as_str).collect();
+        let res = gerar(&lines).map(|bytes| hex::encode(Sha256::digest(&bytes)));
+        resultados.push((&case.name, &case.mind_sha256, res));
+    }
+
+    let base = Path::new(&path).parent().unwrap_or(Path::new("."));
+    for case in &vectors.v1 {
+        let arquivo = base.join(&case.file).to_string_lossy().into_owned();
+        let res = migrar(&arquivo).map(|bytes| hex::encode(Sha256::digest(&bytes)));
+        resultados.push((&case.name, &case.mind_sha256, res));
+    }
+
+    // o treino completo é verboso; o resumo vem no fim
+    let mut falhas = 0;
+    println!();
+    println!("🧠 mind.bin — {} casos, {} migrações v1", vectors.cases.len(), vectors.v1.len());
+
+    for (name, esperado, res) in resultados {
+        match res {
+            Ok(sha) if sha == *esperado => println!("✅ {}", name),
+            Ok(sha) => {
+                falhas += 1;
+                println!("❌ {}: sha256 {} ≠ esperado {}", name, sha, esperado);
+            }
+            Err(e) => {
+                falhas += 1;
+                println!("❌ {}: {}", name, e);
+            }
+        }
+    }
+
+    if falhas > 0 {
+        eprintln!("❌ {} falha(s)", falhas);
+        process::exit(1);
+    }
+    println!("✅ mind.bin idêntico em todos os caminhos");
+}
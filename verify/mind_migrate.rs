@@ -0,0 +1,39 @@
+// =========================================================
+// 🧠 MIND MIGRATE — mind.bin v1 → v2
+// =========================================================
+//
+// uso: mind_migrate <mind_v1.bin> <mind_v2.bin>
+//
+// Reprocessa os registros do v1 com o config dos serviços (TreinoConfig::
+// ledger), confere cada um e grava o v2 com tabela de seções. Registros e
+// timestamp do cabeçalho são mantidos: o v2 é igual ao que o serviço
+// gravaria para o mesmo ledger. O fp_hex muda junto com os bytes.
+
+use std::{env, fs, process};
+
+use terra_dourada_core::fxl_turbo::treino::{migrar_mind_bin, TreinoConfig};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let (Some(origem), Some(destino)) = (args.get(1), args.get(2)) else {
+        eprintln!("uso: mind_migrate <mind_v1.bin> <mind_v2.bin>");
+        process::exit(2);
+    };
+
+    if fs::metadata(destino).is_ok() {
+        eprintln!("❌ {} já existe", destino);
+        process::exit(2);
+    }
+
+    let mut config = TreinoConfig::ledger(String::new());
+    config.caminho_mind_bin = Some(destino.clone());
+
+    match migrar_mind_bin(origem, destino, &config) {
+        Ok(registros) => println!("✅ {} registros migrados para v2 em {}", registros, destino),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -9,7 +9,7 @@ fn string_para_bits(texto: &str, max_bits: usize) -> Vec<u8> {
     let mut bits = Vec::new();
     for byte in texto.as_bytes() {
         for i in (0..8).rev() {
-            bits.push(((byte >> i) & 1) as u8);
+            bits.push((byte >> i) & 1);
         }
     }
 
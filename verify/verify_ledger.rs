@@ -0,0 +1,107 @@
+// =========================================================
+// 📒 VERIFY LEDGER — auditoria offline dos ledgers encadeados
+// =========================================================
+//
+// uso: verify_ledger <ledger> [--head <hex>]
+//      verify_ledger --rechain <ledger_antigo> <ledger_novo>
+//
+// Sem --head, confere só a cadeia (v, prev, entry) e imprime a cabeça.
+// Com --head, exige também que a cabeça seja a publicada pelo serviço
+// (campo ledger_head do GET /state) — pega reescrita do arquivo inteiro.
+//
+// --rechain converte um ledger no formato antigo (v=..|ts=..|...) sem
+// encadeamento: mantém os campos, renumera e encadeia. Não há como provar
+// que o arquivo antigo não foi editado antes; confira-o antes de converter.
+
+use std::{env, fs, process};
+
+use terra_dourada_core::ledger::{format_entry, read_file, verify_ledger, LedgerHead};
+
+fn usage() -> ! {
+    eprintln!("uso: verify_ledger <ledger> [--head <hex>]");
+    eprintln!("     verify_ledger --rechain <ledger_antigo> <ledger_novo>");
+    process::exit(2);
+}
+
+fn rechain(old_path: &str, new_path: &str) {
+    if fs::metadata(new_path).is_ok() {
+        eprintln!("❌ {} já existe", new_path);
+        process::exit(2);
+    }
+
+    let mut head = LedgerHead::genesis();
+    let mut out = String::new();
+
+    for (idx, line) in read_file(old_path).lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut fields: Vec<(&str, &str)> = Vec::new();
+        for part in line.trim().split('|') {
+            let Some((k, v)) = part.split_once('=') else {
+                eprintln!("❌ linha {}: campo sem '=': {:?}", idx + 1, part);
+                process::exit(1);
+            };
+            if k != "v" {
+                fields.push((k, v));
+            }
+        }
+
+        let version = head.version + 1;
+        let (entry_line, entry) = format_entry(version, &head.entry, &fields).unwrap_or_else(|e| {
+            eprintln!("❌ linha {}: {}", idx + 1, e);
+            process::exit(1);
+        });
+        out.push_str(&entry_line);
+        out.push('\n');
+        head = LedgerHead { version, entry };
+    }
+
+    fs::write(new_path, out).unwrap_or_else(|e| {
+        eprintln!("❌ falha ao gravar {}: {}", new_path, e);
+        process::exit(2);
+    });
+    println!("✅ {} entradas encadeadas em {}", head.version, new_path);
+    println!("📒 cabeça {}", head.entry);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.get(1).map(String::as_str) == Some("--rechain") {
+        match (args.get(2), args.get(3)) {
+            (Some(old), Some(new)) => rechain(old, new),
+            _ => usage(),
+        }
+        return;
+    }
+
+    let Some(path) = args.get(1) else { usage() };
+    if fs::metadata(path).is_err() {
+        eprintln!("❌ {} não existe", path);
+        process::exit(2);
+    }
+
+    let expected_head = args
+        .iter()
+        .position(|a| a == "--head")
+        .map(|i| args.get(i + 1).unwrap_or_else(|| usage()).to_ascii_lowercase());
+
+    let head = match verify_ledger(&read_file(path)) {
+        Ok(head) => head,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(1);
+        }
+    };
+
+    println!("🔗 {} entradas encadeadas", head.version);
+    match expected_head {
+        Some(h) if h != head.entry => {
+            eprintln!("❌ cabeça {} ≠ publicada {}", head.entry, h);
+            process::exit(1);
+        }
+        Some(_) => println!("✅ Cadeia íntegra e cabeça confere: {}", head.entry),
+        None => println!("✅ Cadeia íntegra · cabeça {}", head.entry),
+    }
+}